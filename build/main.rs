@@ -0,0 +1,32 @@
+mod map_compiler;
+
+use std::{error::Error, path::PathBuf, result::Result};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let root_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR")?);
+
+    let build_assets_maps_path = root_path.join(PathBuf::from("build_assets/tiled/maps"));
+    let binary_assets_path = root_path.join(PathBuf::from("assets/bin/maps"));
+
+    let mut diagnostics = vec![];
+
+    map_compiler::compile_maps(
+        &build_assets_maps_path,
+        &binary_assets_path,
+        &mut diagnostics,
+    )?;
+
+    println!("cargo:rerun-if-changed=build_assets/tiled/maps");
+    println!("cargo:rerun-if-changed=assets/spritesheets");
+    println!("cargo:rerun-if-changed=build");
+
+    if !diagnostics.is_empty() {
+        for diagnostic in &diagnostics {
+            eprintln!("error: {}", diagnostic);
+        }
+
+        return Err(format!("Failed to compile maps: {} error(s)", diagnostics.len()).into());
+    }
+
+    Ok(())
+}
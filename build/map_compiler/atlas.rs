@@ -0,0 +1,232 @@
+// Packs every tile a map uses into one image, laid out in a square-ish grid
+use super::TILE_PIXELS_SIZE;
+use image::RgbaImage;
+
+// Tiles are surrounded by a copy of their own edge pixels, so filtering at the edge of
+// a tile never samples its neighbour in the atlas
+pub const TILE_PADDING: u64 = 1;
+
+const CELL_SIZE: u64 = TILE_PIXELS_SIZE + TILE_PADDING * 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasLayout {
+    pub columns: u64,
+    pub rows: u64,
+}
+
+impl AtlasLayout {
+    pub fn new(tile_count: u64) -> Self {
+        let mut columns = 1;
+        while columns * columns < tile_count {
+            columns += 1;
+        }
+
+        let rows = tile_count.div_ceil(columns).max(1);
+
+        Self { columns, rows }
+    }
+
+    pub fn width(&self) -> u64 {
+        self.columns * CELL_SIZE
+    }
+
+    pub fn height(&self) -> u64 {
+        self.rows * CELL_SIZE
+    }
+
+    // Top left pixel of the tile itself, inside of its padding
+    pub fn tile_position(&self, tile_id: u64) -> (u64, u64) {
+        (
+            (tile_id % self.columns) * CELL_SIZE + TILE_PADDING,
+            (tile_id / self.columns) * CELL_SIZE + TILE_PADDING,
+        )
+    }
+
+    // [x, y, w, h] as fractions of the atlas size
+    pub fn uv(&self, tile_id: u64) -> [f32; 4] {
+        let (x, y) = self.tile_position(tile_id);
+        let (width, height) = (self.width() as f32, self.height() as f32);
+
+        [
+            x as f32 / width,
+            y as f32 / height,
+            TILE_PIXELS_SIZE as f32 / width,
+            TILE_PIXELS_SIZE as f32 / height,
+        ]
+    }
+}
+
+// Where a tile's pixels come from in its tileset image
+pub struct AtlasTile<'a> {
+    pub image: &'a RgbaImage,
+    pub x: u64,
+    pub y: u64,
+}
+
+pub fn pack_atlas(layout: &AtlasLayout, tiles: &[AtlasTile]) -> RgbaImage {
+    let mut atlas = RgbaImage::new(layout.width() as u32, layout.height() as u32);
+
+    let size = TILE_PIXELS_SIZE as i64;
+    let padding = TILE_PADDING as i64;
+
+    for (tile_id, tile) in tiles.iter().enumerate() {
+        let (atlas_x, atlas_y) = layout.tile_position(tile_id as u64);
+
+        for y in -padding..(size + padding) {
+            for x in -padding..(size + padding) {
+                // Clamping into the tile extrudes its edges out into the padding
+                let src_x = tile.x + x.max(0).min(size - 1) as u64;
+                let src_y = tile.y + y.max(0).min(size - 1) as u64;
+
+                atlas.put_pixel(
+                    (atlas_x as i64 + x) as u32,
+                    (atlas_y as i64 + y) as u32,
+                    *tile.image.get_pixel(src_x as u32, src_y as u32),
+                );
+            }
+        }
+    }
+
+    atlas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn lays_tiles_out_in_a_square_ish_grid() {
+        assert_eq!(
+            AtlasLayout::new(0),
+            AtlasLayout {
+                columns: 1,
+                rows: 1
+            }
+        );
+        assert_eq!(
+            AtlasLayout::new(1),
+            AtlasLayout {
+                columns: 1,
+                rows: 1
+            }
+        );
+        assert_eq!(
+            AtlasLayout::new(4),
+            AtlasLayout {
+                columns: 2,
+                rows: 2
+            }
+        );
+        assert_eq!(
+            AtlasLayout::new(5),
+            AtlasLayout {
+                columns: 3,
+                rows: 2
+            }
+        );
+        assert_eq!(
+            AtlasLayout::new(10),
+            AtlasLayout {
+                columns: 4,
+                rows: 3
+            }
+        );
+    }
+
+    #[test]
+    fn places_tiles_inside_of_their_padding() {
+        let layout = AtlasLayout::new(5);
+
+        assert_eq!(layout.width(), 3 * CELL_SIZE);
+        assert_eq!(layout.height(), 2 * CELL_SIZE);
+
+        assert_eq!(layout.tile_position(0), (TILE_PADDING, TILE_PADDING));
+        assert_eq!(
+            layout.tile_position(2),
+            (2 * CELL_SIZE + TILE_PADDING, TILE_PADDING)
+        );
+        assert_eq!(
+            layout.tile_position(4),
+            (CELL_SIZE + TILE_PADDING, CELL_SIZE + TILE_PADDING)
+        );
+
+        let [x, y, width, height] = layout.uv(4);
+        assert_eq!(x, (CELL_SIZE + TILE_PADDING) as f32 / layout.width() as f32);
+        assert_eq!(
+            y,
+            (CELL_SIZE + TILE_PADDING) as f32 / layout.height() as f32
+        );
+        assert_eq!(width, TILE_PIXELS_SIZE as f32 / layout.width() as f32);
+        assert_eq!(height, TILE_PIXELS_SIZE as f32 / layout.height() as f32);
+    }
+
+    #[test]
+    fn extrudes_tile_edges_into_the_padding() {
+        let size = TILE_PIXELS_SIZE as u32;
+
+        // Two tiles side by side, where every pixel is different
+        let tileset = RgbaImage::from_fn(size * 2, size, |x, y| Rgba([x as u8, y as u8, 0, 255]));
+        let tiles = [
+            AtlasTile {
+                image: &tileset,
+                x: 0,
+                y: 0,
+            },
+            AtlasTile {
+                image: &tileset,
+                x: TILE_PIXELS_SIZE,
+                y: 0,
+            },
+        ];
+
+        let layout = AtlasLayout::new(tiles.len() as u64);
+        let atlas = pack_atlas(&layout, &tiles);
+
+        assert_eq!(atlas.width() as u64, layout.width());
+        assert_eq!(atlas.height() as u64, layout.height());
+
+        for (tile_id, tile) in tiles.iter().enumerate() {
+            let (atlas_x, atlas_y) = layout.tile_position(tile_id as u64);
+            let (atlas_x, atlas_y) = (atlas_x as u32, atlas_y as u32);
+            let tile_x = tile.x as u32;
+            let last = size - 1;
+
+            // The tile itself is copied as is
+            for y in 0..size {
+                for x in 0..size {
+                    assert_eq!(
+                        atlas.get_pixel(atlas_x + x, atlas_y + y),
+                        tileset.get_pixel(tile_x + x, y)
+                    );
+                }
+            }
+
+            // Edges and corners are repeated out into the padding
+            assert_eq!(
+                atlas.get_pixel(atlas_x - 1, atlas_y + 5),
+                tileset.get_pixel(tile_x, 5)
+            );
+            assert_eq!(
+                atlas.get_pixel(atlas_x + size, atlas_y + 5),
+                tileset.get_pixel(tile_x + last, 5)
+            );
+            assert_eq!(
+                atlas.get_pixel(atlas_x + 5, atlas_y - 1),
+                tileset.get_pixel(tile_x + 5, 0)
+            );
+            assert_eq!(
+                atlas.get_pixel(atlas_x + 5, atlas_y + size),
+                tileset.get_pixel(tile_x + 5, last)
+            );
+            assert_eq!(
+                atlas.get_pixel(atlas_x - 1, atlas_y - 1),
+                tileset.get_pixel(tile_x, 0)
+            );
+            assert_eq!(
+                atlas.get_pixel(atlas_x + size, atlas_y + size),
+                tileset.get_pixel(tile_x + last, last)
+            );
+        }
+    }
+}
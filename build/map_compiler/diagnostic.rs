@@ -0,0 +1,43 @@
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub enum Location {
+    Map,
+    Layer { layer: String },
+    LayerTile { layer: String, x: i64, y: i64 },
+    TileSet { tile_set: String },
+    TileSetTile { tile_set: String, tile_id: u64 },
+    Object { layer: String, object: String },
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Map => Ok(()),
+            Self::Layer { layer } => write!(f, " layer \"{}\":", layer),
+            Self::LayerTile { layer, x, y } => {
+                write!(f, " layer \"{}\": tile ({}, {}):", layer, x, y)
+            }
+            Self::TileSet { tile_set } => write!(f, " tileset \"{}\":", tile_set),
+            Self::TileSetTile { tile_set, tile_id } => {
+                write!(f, " tileset \"{}\": tile {}:", tile_set, tile_id)
+            }
+            Self::Object { layer, object } => {
+                write!(f, " layer \"{}\": object \"{}\":", layer, object)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub file: String,
+    pub location: Location,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{} {}", self.file, self.location, self.message)
+    }
+}
@@ -0,0 +1,88 @@
+// Map binaries are a small container around bincode sections:
+//
+//   magic: [u8; 8]
+//   version: u32
+//   hash: u64 (FNV-1a of everything after the header)
+//   section_count: u32
+//   sections: [(tag: [u8; 4], offset: u64, length: u64); section_count]
+//   ...section data
+//
+// Chunks are serialized one at a time into CDAT so the game can decode them as they're needed,
+// with CHNK listing where each one is as (chunk x, chunk y, offset into CDAT, length).
+//
+// All numbers are little endian. These must be kept in sync with src/game/maps/format.rs
+use super::types::TileMap;
+
+const MAGIC: [u8; 8] = *b"RGPMAP\0\0";
+const VERSION: u32 = 11;
+
+const SECTION_INFO: [u8; 4] = *b"INFO";
+const SECTION_ATLAS: [u8; 4] = *b"ATLS";
+const SECTION_TILES: [u8; 4] = *b"TILE";
+const SECTION_LAYERS: [u8; 4] = *b"LAYR";
+const SECTION_CHUNK_INDEX: [u8; 4] = *b"CHNK";
+const SECTION_CHUNK_DATA: [u8; 4] = *b"CDAT";
+const SECTION_OBJECTS: [u8; 4] = *b"OBJS";
+const SECTION_CONNECTIONS: [u8; 4] = *b"CONN";
+
+const HEADER_LEN: usize = 8 + 4 + 8 + 4;
+const SECTION_ENTRY_LEN: usize = 4 + 8 + 8;
+
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+pub fn write_map_binary(tile_map: &TileMap) -> Result<Vec<u8>, bincode::Error> {
+    let mut chunk_index = vec![];
+    let mut chunk_data = vec![];
+
+    for chunk in &tile_map.chunks {
+        let data = bincode::serialize(&chunk.layers)?;
+
+        chunk_index.push((chunk.x, chunk.y, chunk_data.len() as u64, data.len() as u64));
+        chunk_data.extend_from_slice(&data);
+    }
+
+    let sections = vec![
+        (
+            SECTION_INFO,
+            bincode::serialize(&(tile_map.width, tile_map.height))?,
+        ),
+        (SECTION_ATLAS, bincode::serialize(&tile_map.atlas)?),
+        (SECTION_TILES, bincode::serialize(&tile_map.tiles)?),
+        (SECTION_LAYERS, bincode::serialize(&tile_map.layers)?),
+        (SECTION_CHUNK_INDEX, bincode::serialize(&chunk_index)?),
+        (SECTION_CHUNK_DATA, chunk_data),
+        (SECTION_OBJECTS, bincode::serialize(&tile_map.objects)?),
+        (
+            SECTION_CONNECTIONS,
+            bincode::serialize(&tile_map.connections)?,
+        ),
+    ];
+
+    let mut body = vec![];
+    let mut offset = (HEADER_LEN + SECTION_ENTRY_LEN * sections.len()) as u64;
+
+    for (tag, data) in &sections {
+        body.extend_from_slice(tag);
+        body.extend_from_slice(&offset.to_le_bytes());
+        body.extend_from_slice(&(data.len() as u64).to_le_bytes());
+
+        offset += data.len() as u64;
+    }
+
+    for (_, data) in &sections {
+        body.extend_from_slice(data);
+    }
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + body.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&fnv1a_hash(&body).to_le_bytes());
+    bytes.extend_from_slice(&(sections.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&body);
+
+    Ok(bytes)
+}
@@ -0,0 +1,949 @@
+mod atlas;
+mod diagnostic;
+mod format;
+mod output;
+mod raw;
+mod tmx;
+mod types;
+
+pub use diagnostic::{Diagnostic, Location};
+pub use format::write_map_binary;
+pub use output::compile_maps;
+pub use types::TileMap;
+
+use atlas::{AtlasLayout, AtlasTile};
+use raw::{
+    RawLayer, RawMap, RawObject, RawObjectGroup, RawProperty, RawTile, RawTileLayer, RawTileSet,
+    RawValue,
+};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::Path,
+};
+use types::{
+    Atlas, DrawOrder, EntityName, GameDirection, ItemId, MapConnection, MapName, MapObject,
+    MapObjectKind, StateCondition, StateName, Tile, TileAnimationFrame, TileChunk, TileLayer,
+    TileProperty, TriggerEvent,
+};
+
+// Must match config::TILE_PIXELS_SIZE_USIZE
+const TILE_PIXELS_SIZE: u64 = 16;
+
+// Must match maps::CHUNK_SIZE
+const CHUNK_SIZE: u64 = 16;
+
+// Tiled stores flipped and rotated tiles as flags in the top bits of the tile id
+const TILE_FLIP_FLAGS: u64 = 0xE000_0000;
+
+pub struct CompiledMap {
+    pub tile_map: TileMap,
+    pub atlas: image::RgbaImage,
+}
+
+// atlas_filename is where the game will load the atlas image from, inside of assets/
+pub fn compile_map_file(path: &Path, atlas_filename: &str) -> Result<CompiledMap, Vec<Diagnostic>> {
+    let mut compiler = MapCompiler {
+        file: path.display().to_string(),
+        atlas_filename: atlas_filename.to_string(),
+        diagnostics: vec![],
+    };
+
+    let compiled_map = match tmx::read_map(path) {
+        Ok(raw_map) => compiler.compile(&raw_map),
+        Err(message) => {
+            compiler.error(Location::Map, message);
+            None
+        }
+    };
+
+    match compiled_map {
+        Some(compiled_map) if compiler.diagnostics.is_empty() => Ok(compiled_map),
+        _ => Err(compiler.diagnostics),
+    }
+}
+
+fn read_properties(properties: &[RawProperty]) -> HashMap<&str, &RawValue> {
+    properties
+        .iter()
+        .map(|property| (property.name.as_str(), &property.value))
+        .collect()
+}
+
+// The game looks these up by name, any other tile properties are passed through as they are
+fn validate_tile_property(property: &RawProperty) -> Result<(), String> {
+    let (expected, valid) = match property.name.as_str() {
+        "blocks_movement" | "slippery" => ("a bool", matches!(property.value, RawValue::Bool(_))),
+        "footstep_sound" | "encounter_zone" => ("a string", property.value.as_str().is_some()),
+        "animation_offset" => ("a positive int", property.value.as_u64().is_some()),
+        "ledge_direction" => (
+            "one of Up, Down, Left or Right",
+            property
+                .value
+                .as_str()
+                .and_then(GameDirection::from_name)
+                .is_some(),
+        ),
+        _ => return Ok(()),
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Property {} should be {}, found {}",
+            property.name, expected, property.value
+        ))
+    }
+}
+
+fn tile_property(value: &RawValue) -> TileProperty {
+    match value {
+        RawValue::Bool(value) => TileProperty::Bool(*value),
+        RawValue::Int(value) => TileProperty::Int(*value),
+        RawValue::Float(value) => TileProperty::Float(*value),
+        RawValue::String(value) => TileProperty::String(value.clone()),
+    }
+}
+
+// Splits the layers up into square chunks, leaving out any that would be empty
+fn build_chunks(width: u64, height: u64, layer_tile_ids: &[Vec<Option<u64>>]) -> Vec<TileChunk> {
+    let mut chunks = vec![];
+
+    for chunk_y in 0..height.div_ceil(CHUNK_SIZE) {
+        for chunk_x in 0..width.div_ceil(CHUNK_SIZE) {
+            let layers: Vec<Vec<Option<u64>>> = layer_tile_ids
+                .iter()
+                .map(|tile_ids| {
+                    let chunk_tile_ids: Vec<Option<u64>> = (0..CHUNK_SIZE * CHUNK_SIZE)
+                        .map(|idx| {
+                            let x = chunk_x * CHUNK_SIZE + idx % CHUNK_SIZE;
+                            let y = chunk_y * CHUNK_SIZE + idx / CHUNK_SIZE;
+
+                            if x < width && y < height {
+                                tile_ids[(y * width + x) as usize]
+                            } else {
+                                None
+                            }
+                        })
+                        .collect();
+
+                    // Layers with nothing in this chunk are left empty to save space
+                    if chunk_tile_ids.iter().all(Option::is_none) {
+                        vec![]
+                    } else {
+                        chunk_tile_ids
+                    }
+                })
+                .collect();
+
+            if layers.iter().any(|tile_ids| !tile_ids.is_empty()) {
+                chunks.push(TileChunk {
+                    x: chunk_x,
+                    y: chunk_y,
+                    layers,
+                });
+            }
+        }
+    }
+
+    chunks
+}
+
+// A tileset along with the range of map tile ids that point into it
+struct TileSetSource<'a> {
+    raw: &'a RawTileSet,
+    min_id: u64,
+    max_id: u64,
+    image: Option<image::RgbaImage>,
+}
+
+impl<'a> TileSetSource<'a> {
+    fn contains(&self, tile_id: u64) -> bool {
+        self.min_id <= tile_id && tile_id <= self.max_id
+    }
+
+    fn tile(&self, tile_id: u64) -> Option<&'a RawTile> {
+        self.raw
+            .tiles
+            .iter()
+            .find(|tile| self.min_id + tile.id == tile_id)
+    }
+}
+
+struct MapCompiler {
+    file: String,
+    atlas_filename: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl MapCompiler {
+    fn error(&mut self, location: Location, message: String) {
+        self.diagnostics.push(Diagnostic {
+            file: self.file.clone(),
+            location,
+            message,
+        });
+    }
+
+    fn compile(&mut self, raw_map: &RawMap) -> Option<CompiledMap> {
+        if raw_map.tilewidth != TILE_PIXELS_SIZE || raw_map.tileheight != TILE_PIXELS_SIZE {
+            self.error(
+                Location::Map,
+                format!(
+                    "Map tiles are {}x{}, expected {}x{}",
+                    raw_map.tilewidth, raw_map.tileheight, TILE_PIXELS_SIZE, TILE_PIXELS_SIZE
+                ),
+            );
+        }
+
+        let sources: Vec<TileSetSource> = raw_map
+            .tilesets
+            .iter()
+            .map(|tile_set| self.validate_tile_set(tile_set))
+            .collect();
+
+        let tile_layers: Vec<&RawTileLayer> = raw_map
+            .layers
+            .iter()
+            .filter_map(|layer| match layer {
+                RawLayer::TileLayer(tile_layer) => Some(tile_layer),
+                _ => None,
+            })
+            .collect();
+
+        if tile_layers.is_empty() {
+            self.error(Location::Map, "Map has no tile layers".to_string());
+        }
+
+        let (layers, mut layer_tile_ids): (Vec<TileLayer>, Vec<Vec<Option<u64>>>) = tile_layers
+            .into_iter()
+            .filter_map(|layer| self.compile_tile_layer(raw_map, layer, &sources))
+            .unzip();
+
+        let objects = raw_map
+            .layers
+            .iter()
+            .filter_map(|layer| match layer {
+                RawLayer::ObjectGroup(object_group) => Some(object_group),
+                _ => None,
+            })
+            .flat_map(|object_group| {
+                object_group
+                    .objects
+                    .iter()
+                    .map(move |object| (object_group, object))
+            })
+            .filter_map(|(object_group, object)| self.compile_object(raw_map, object_group, object))
+            .collect();
+
+        let connections = self.compile_connections(raw_map);
+
+        // Only tiles that get drawn go into the atlas, including every frame of their animations
+        let mut used_ids: BTreeSet<u64> = layer_tile_ids
+            .iter()
+            .flat_map(|tile_ids| tile_ids.iter().flatten().copied())
+            .collect();
+
+        // Frames can be animated tiles themselves, so keep going until no new frames turn up
+        let mut unchecked_ids: Vec<u64> = used_ids.iter().copied().collect();
+
+        while let Some(id) = unchecked_ids.pop() {
+            let source = match sources.iter().find(|source| source.contains(id)) {
+                Some(source) => source,
+                None => continue,
+            };
+
+            for frame in source
+                .tile(id)
+                .and_then(|tile| tile.animation.as_ref())
+                .into_iter()
+                .flatten()
+            {
+                let frame_id = source.min_id + frame.tileid;
+
+                if used_ids.insert(frame_id) {
+                    unchecked_ids.push(frame_id);
+                }
+            }
+        }
+
+        let atlas_ids: HashMap<u64, u64> = used_ids
+            .iter()
+            .enumerate()
+            .map(|(atlas_id, &id)| (id, atlas_id as u64))
+            .collect();
+
+        let layout = AtlasLayout::new(used_ids.len() as u64);
+
+        let atlas_tiles = used_ids
+            .iter()
+            .map(|&id| {
+                let source = sources.iter().find(|source| source.contains(id))?;
+                let columns = source.raw.columns.max(1);
+                let local_id = id - source.min_id;
+
+                Some(AtlasTile {
+                    image: source.image.as_ref()?,
+                    x: (local_id % columns) * TILE_PIXELS_SIZE,
+                    y: (local_id / columns) * TILE_PIXELS_SIZE,
+                })
+            })
+            .collect::<Option<Vec<AtlasTile>>>()?;
+
+        let tiles = used_ids
+            .iter()
+            .filter_map(|&id| {
+                let source = sources.iter().find(|source| source.contains(id))?;
+                let tile = source.tile(id)?;
+
+                if tile.properties.is_empty() && tile.animation.is_none() {
+                    return None;
+                }
+
+                let animation = tile.animation.as_ref().map(|frames| {
+                    frames
+                        .iter()
+                        .map(|frame| TileAnimationFrame {
+                            tile_id: atlas_ids[&(source.min_id + frame.tileid)],
+                            duration_millis: frame.duration,
+                        })
+                        .collect()
+                });
+
+                Some(Tile {
+                    id: atlas_ids[&id],
+                    properties: tile
+                        .properties
+                        .iter()
+                        .map(|property| (property.name.clone(), tile_property(&property.value)))
+                        .collect(),
+                    animation,
+                })
+            })
+            .collect();
+
+        for tile_ids in &mut layer_tile_ids {
+            for tile_id in tile_ids.iter_mut().flatten() {
+                *tile_id = atlas_ids[tile_id];
+            }
+        }
+
+        let chunks = build_chunks(raw_map.width, raw_map.height, &layer_tile_ids);
+
+        Some(CompiledMap {
+            tile_map: TileMap {
+                width: raw_map.width,
+                height: raw_map.height,
+                atlas: Atlas {
+                    image_filename: self.atlas_filename.clone(),
+                    width: layout.width(),
+                    height: layout.height(),
+                    uvs: (0..used_ids.len() as u64)
+                        .map(|atlas_id| layout.uv(atlas_id))
+                        .collect(),
+                },
+                tiles,
+                layers,
+                chunks,
+                objects,
+                connections,
+            },
+            atlas: atlas::pack_atlas(&layout, &atlas_tiles),
+        })
+    }
+
+    fn validate_tile_set<'a>(&mut self, tile_set: &'a RawTileSet) -> TileSetSource<'a> {
+        let location = Location::TileSet {
+            tile_set: tile_set.name.clone(),
+        };
+
+        let min_id = tile_set.firstgid.saturating_sub(1);
+        let max_id = min_id + tile_set.tilecount.saturating_sub(1);
+
+        let image = match image::open(&tile_set.image) {
+            Ok(image) => Some(image.to_rgba8()),
+            Err(e) => {
+                self.error(
+                    location.clone(),
+                    format!("Couldn't load image {}: {}", tile_set.image.display(), e),
+                );
+                None
+            }
+        };
+
+        if let Some(image) = &image {
+            if image.dimensions() != (tile_set.imagewidth as u32, tile_set.imageheight as u32) {
+                self.error(
+                    location.clone(),
+                    format!(
+                        "Image is {}x{}, but the tileset expects {}x{}",
+                        image.width(),
+                        image.height(),
+                        tile_set.imagewidth,
+                        tile_set.imageheight
+                    ),
+                );
+            }
+        }
+
+        if tile_set.tilewidth != TILE_PIXELS_SIZE || tile_set.tileheight != TILE_PIXELS_SIZE {
+            self.error(
+                location.clone(),
+                format!(
+                    "Tiles are {}x{}, expected {}x{}",
+                    tile_set.tilewidth, tile_set.tileheight, TILE_PIXELS_SIZE, TILE_PIXELS_SIZE
+                ),
+            );
+        }
+
+        if !tile_set.imagewidth.is_multiple_of(TILE_PIXELS_SIZE)
+            || !tile_set.imageheight.is_multiple_of(TILE_PIXELS_SIZE)
+        {
+            self.error(
+                location.clone(),
+                format!(
+                    "Image is {}x{}, which isn't a multiple of {}",
+                    tile_set.imagewidth, tile_set.imageheight, TILE_PIXELS_SIZE
+                ),
+            );
+        }
+
+        if tile_set.columns == 0 || !tile_set.tilecount.is_multiple_of(tile_set.columns) {
+            self.error(
+                location.clone(),
+                format!(
+                    "{} tiles can't be split into {} columns",
+                    tile_set.tilecount, tile_set.columns
+                ),
+            );
+        } else if tile_set.columns * TILE_PIXELS_SIZE > tile_set.imagewidth
+            || tile_set.tilecount / tile_set.columns * TILE_PIXELS_SIZE > tile_set.imageheight
+        {
+            self.error(
+                location,
+                format!(
+                    "{} tiles in {} columns don't fit in the image",
+                    tile_set.tilecount, tile_set.columns
+                ),
+            );
+        }
+
+        for tile in &tile_set.tiles {
+            let location = Location::TileSetTile {
+                tile_set: tile_set.name.clone(),
+                tile_id: tile.id,
+            };
+
+            if tile.id >= tile_set.tilecount {
+                self.error(
+                    location.clone(),
+                    "Tile is outside of the tileset".to_string(),
+                );
+            }
+
+            // Walls and water used to be tile types, make sure old tilesets don't silently lose them
+            if let Some(tile_type) = &tile.tile_type {
+                self.error(
+                    location.clone(),
+                    format!(
+                        "Tile types aren't supported anymore, replace {:?} with properties like blocks_movement",
+                        tile_type
+                    ),
+                );
+            }
+
+            for property in &tile.properties {
+                if let Err(message) = validate_tile_property(property) {
+                    self.error(location.clone(), message);
+                }
+            }
+
+            for frame in tile.animation.iter().flatten() {
+                if frame.tileid >= tile_set.tilecount {
+                    self.error(
+                        location.clone(),
+                        format!(
+                            "Animation frame points at tile {}, which is outside of this tileset",
+                            frame.tileid
+                        ),
+                    );
+                }
+
+                if frame.duration == 0 {
+                    self.error(
+                        location.clone(),
+                        format!("Animation frame for tile {} has no duration", frame.tileid),
+                    );
+                }
+            }
+        }
+
+        // Tiles get packed out of the image, so it must be the size the tileset says it is
+        let image = image.filter(|image| {
+            image.dimensions() == (tile_set.imagewidth as u32, tile_set.imageheight as u32)
+        });
+
+        TileSetSource {
+            raw: tile_set,
+            min_id,
+            max_id,
+            image,
+        }
+    }
+
+    fn compile_tile_layer(
+        &mut self,
+        raw_map: &RawMap,
+        layer: &RawTileLayer,
+        sources: &[TileSetSource],
+    ) -> Option<(TileLayer, Vec<Option<u64>>)> {
+        let location = Location::Layer {
+            layer: layer.name.clone(),
+        };
+
+        let draw_order = match read_properties(&layer.properties).get("draw_order") {
+            Some(value) => {
+                let draw_order = value.as_str().and_then(DrawOrder::from_name);
+
+                if draw_order.is_none() {
+                    self.error(
+                        location.clone(),
+                        format!(
+                            "Unknown draw_order {}, expected below, above or sorted",
+                            value
+                        ),
+                    );
+                }
+
+                draw_order
+            }
+            None => Some(DrawOrder::Below),
+        };
+
+        let mut tile_ids = vec![None; (raw_map.width * raw_map.height) as usize];
+
+        for chunk in &layer.chunks {
+            let expected_tiles = chunk.width * chunk.height;
+            if chunk.data.len() as u64 != expected_tiles {
+                self.error(
+                    location.clone(),
+                    if raw_map.infinite {
+                        format!(
+                            "Chunk at ({}, {}) has {} tiles, expected {}",
+                            chunk.x,
+                            chunk.y,
+                            chunk.data.len(),
+                            expected_tiles
+                        )
+                    } else {
+                        format!(
+                            "Layer has {} tiles, expected {}",
+                            chunk.data.len(),
+                            expected_tiles
+                        )
+                    },
+                );
+                return None;
+            }
+
+            for (idx, &id) in chunk.data.iter().enumerate() {
+                let x = chunk.x + (idx as u64 % chunk.width) as i64;
+                let y = chunk.y + (idx as u64 / chunk.width) as i64;
+
+                let tile_location = Location::LayerTile {
+                    layer: layer.name.clone(),
+                    x,
+                    y,
+                };
+
+                let tile_id = if id == 0 {
+                    None
+                } else if id & TILE_FLIP_FLAGS != 0 {
+                    self.error(
+                        tile_location,
+                        "Flipped and rotated tiles aren't supported".to_string(),
+                    );
+                    None
+                } else if sources.iter().any(|source| source.contains(id - 1)) {
+                    Some(id - 1) // Fix the offset by 1
+                } else {
+                    self.error(
+                        tile_location,
+                        format!("Tile id {} doesn't belong to any tileset", id),
+                    );
+                    None
+                };
+
+                // Chunks always fit inside of the map, since the map was sized around them
+                let idx =
+                    (y - raw_map.origin_y) as u64 * raw_map.width + (x - raw_map.origin_x) as u64;
+                tile_ids[idx as usize] = tile_id;
+            }
+        }
+
+        Some((
+            TileLayer {
+                name: layer.name.clone(),
+                draw_order: draw_order?,
+            },
+            tile_ids,
+        ))
+    }
+
+    // Connections are map properties named after the edge, like connection_up = "Varrock", with an
+    // optional connection_up_offset
+    fn compile_connections(&mut self, raw_map: &RawMap) -> Vec<MapConnection> {
+        let properties = read_properties(&raw_map.properties);
+
+        let directions = [
+            ("up", GameDirection::Up),
+            ("down", GameDirection::Down),
+            ("left", GameDirection::Left),
+            ("right", GameDirection::Right),
+        ];
+
+        let mut connections = vec![];
+
+        for (edge, direction) in directions.iter() {
+            let key = format!("connection_{}", edge);
+            let offset_key = format!("connection_{}_offset", edge);
+
+            if !properties.contains_key(key.as_str()) {
+                if properties.contains_key(offset_key.as_str()) {
+                    self.error(
+                        Location::Map,
+                        format!("Property {} is set without {}", offset_key, key),
+                    );
+                }
+
+                continue;
+            }
+
+            let map = self.name_property(&Location::Map, &properties, &key, MapName::from_name);
+
+            let offset = match properties.get(offset_key.as_str()) {
+                Some(RawValue::Int(offset)) => Some(*offset),
+                Some(value) => {
+                    self.error(
+                        Location::Map,
+                        format!("Property {} should be an int, found {}", offset_key, value),
+                    );
+                    None
+                }
+                None => Some(0),
+            };
+
+            if let (Some(map), Some(offset)) = (map, offset) {
+                connections.push(MapConnection {
+                    direction: *direction,
+                    map,
+                    offset,
+                });
+            }
+        }
+
+        connections
+    }
+
+    fn compile_object(
+        &mut self,
+        raw_map: &RawMap,
+        object_group: &RawObjectGroup,
+        object: &RawObject,
+    ) -> Option<MapObject> {
+        let location = Location::Object {
+            layer: object_group.name.clone(),
+            object: if object.name.is_empty() {
+                format!("#{}", object.id)
+            } else {
+                object.name.clone()
+            },
+        };
+
+        let properties = read_properties(&object.properties);
+
+        let kind = match object.object_type.as_str() {
+            "Door" => {
+                let door_id = self.u64_property(&location, &properties, "door_id");
+                let to_map =
+                    self.name_property(&location, &properties, "to_map", MapName::from_name);
+                let to_id = self.u64_property(&location, &properties, "to_id");
+                let facing =
+                    self.name_property(&location, &properties, "facing", GameDirection::from_name);
+
+                match (door_id, to_map, to_id, facing) {
+                    (Some(door_id), Some(to_map), Some(to_id), Some(facing)) => {
+                        Some(MapObjectKind::Door {
+                            door_id,
+                            to_map,
+                            to_id,
+                            facing,
+                        })
+                    }
+                    _ => None,
+                }
+            }
+            "Sign" => self
+                .string_property(&location, &properties, "text")
+                .map(|text| MapObjectKind::Sign { text }),
+            "Npc" => {
+                let entity_name = self.name_property(
+                    &location,
+                    &properties,
+                    "entity_name",
+                    EntityName::from_name,
+                );
+
+                // Facing is optional for NPCs, so only report it when it's wrong
+                let facing = if properties.contains_key("facing") {
+                    self.name_property(&location, &properties, "facing", GameDirection::from_name)
+                        .map(Some)
+                } else {
+                    Some(None)
+                };
+
+                match (entity_name, facing) {
+                    (Some(entity_name), Some(facing)) => Some(MapObjectKind::Npc {
+                        entity_name,
+                        facing,
+                    }),
+                    _ => None,
+                }
+            }
+            "Trigger" => {
+                let event = if properties.contains_key("event") {
+                    self.name_property(&location, &properties, "event", TriggerEvent::from_name)
+                } else {
+                    Some(TriggerEvent::Step)
+                };
+                let function = self.string_property(&location, &properties, "function");
+                let conditions = if properties.contains_key("condition") {
+                    self.conditions_property(&location, &properties, "condition")
+                } else {
+                    Some(vec![])
+                };
+                let once = if properties.contains_key("once") {
+                    self.bool_property(&location, &properties, "once")
+                } else {
+                    Some(false)
+                };
+
+                match (event, function, conditions, once) {
+                    (Some(event), Some(function), Some(conditions), Some(once)) => {
+                        Some(MapObjectKind::Trigger {
+                            event,
+                            function,
+                            conditions,
+                            once,
+                        })
+                    }
+                    _ => None,
+                }
+            }
+            "Boulder" => Some(MapObjectKind::Boulder),
+            "PressurePlate" => self
+                .name_property(&location, &properties, "state", StateName::from_name)
+                .map(|state| MapObjectKind::PressurePlate { state }),
+            "Gate" => self
+                .conditions_property(&location, &properties, "condition")
+                .map(|conditions| MapObjectKind::Gate { conditions }),
+            "Pickup" => {
+                let item = self.name_property(&location, &properties, "item", ItemId::from_name);
+                let quantity = if properties.contains_key("quantity") {
+                    self.u64_property(&location, &properties, "quantity")
+                } else {
+                    Some(1)
+                };
+
+                if quantity == Some(0) {
+                    self.error(
+                        location.clone(),
+                        "Property quantity should be at least 1".to_string(),
+                    );
+                }
+
+                match (item, quantity) {
+                    (Some(item), Some(quantity)) if quantity > 0 => {
+                        Some(MapObjectKind::Pickup { item, quantity })
+                    }
+                    _ => None,
+                }
+            }
+            object_type => {
+                self.error(
+                    location.clone(),
+                    format!(
+                        "Unknown object type {:?}, expected Door, Sign, Npc, Trigger, Boulder, PressurePlate, Gate or Pickup",
+                        object_type
+                    ),
+                );
+                None
+            }
+        };
+
+        // Tiled stores objects in pixels, but the game works in tiles
+        let x = object.x / raw_map.tilewidth as f64 - raw_map.origin_x as f64;
+        let y = object.y / raw_map.tileheight as f64 - raw_map.origin_y as f64;
+
+        if x < 0. || y < 0. || x >= raw_map.width as f64 || y >= raw_map.height as f64 {
+            self.error(location, "Object is outside of the map".to_string());
+            return None;
+        }
+
+        let width = ((object.width / raw_map.tilewidth as f64) as u64).max(1);
+        let height = ((object.height / raw_map.tileheight as f64) as u64).max(1);
+
+        if x as u64 + width > raw_map.width as u64 || y as u64 + height > raw_map.height as u64 {
+            self.error(location, "Object runs off the edge of the map".to_string());
+            return None;
+        }
+
+        Some(MapObject {
+            id: object.id,
+            name: object.name.clone(),
+            x: x as u64,
+            y: y as u64,
+            width,
+            height,
+            kind: kind?,
+        })
+    }
+
+    fn property<'a>(
+        &mut self,
+        location: &Location,
+        properties: &HashMap<&str, &'a RawValue>,
+        key: &str,
+    ) -> Option<&'a RawValue> {
+        let value = properties.get(key).copied();
+
+        if value.is_none() {
+            self.error(location.clone(), format!("Missing property: {}", key));
+        }
+
+        value
+    }
+
+    fn string_property(
+        &mut self,
+        location: &Location,
+        properties: &HashMap<&str, &RawValue>,
+        key: &str,
+    ) -> Option<String> {
+        let value = self.property(location, properties, key)?;
+        let string = value.as_str().map(str::to_string);
+
+        if string.is_none() {
+            self.error(
+                location.clone(),
+                format!("Property {} should be a string, found {}", key, value),
+            );
+        }
+
+        string
+    }
+
+    fn u64_property(
+        &mut self,
+        location: &Location,
+        properties: &HashMap<&str, &RawValue>,
+        key: &str,
+    ) -> Option<u64> {
+        let value = self.property(location, properties, key)?;
+        let int = value.as_u64();
+
+        if int.is_none() {
+            self.error(
+                location.clone(),
+                format!("Property {} should be a positive int, found {}", key, value),
+            );
+        }
+
+        int
+    }
+
+    fn bool_property(
+        &mut self,
+        location: &Location,
+        properties: &HashMap<&str, &RawValue>,
+        key: &str,
+    ) -> Option<bool> {
+        let value = self.property(location, properties, key)?;
+        let boolean = value.as_bool();
+
+        if boolean.is_none() {
+            self.error(
+                location.clone(),
+                format!("Property {} should be a bool, found {}", key, value),
+            );
+        }
+
+        boolean
+    }
+
+    // Comma separated states, e.g. "MetWiseOldMan, !map:GateOpen". A ! means the state mustn't be
+    // set, and map: looks in the map's states instead of the world's
+    fn conditions_property(
+        &mut self,
+        location: &Location,
+        properties: &HashMap<&str, &RawValue>,
+        key: &str,
+    ) -> Option<Vec<StateCondition>> {
+        let conditions = self.string_property(location, properties, key)?;
+
+        let mut parsed = vec![];
+        let mut is_valid = true;
+
+        for condition in conditions.split(',').map(str::trim) {
+            let (is_set, condition) = match condition.strip_prefix('!') {
+                Some(condition) => (false, condition),
+                None => (true, condition),
+            };
+            let (in_map, name) = match condition.strip_prefix("map:") {
+                Some(name) => (true, name),
+                None => (false, condition),
+            };
+
+            match StateName::from_name(name) {
+                Some(state) => parsed.push(StateCondition {
+                    state,
+                    in_map,
+                    is_set,
+                }),
+                None => {
+                    self.error(
+                        location.clone(),
+                        format!("Empty state in property {}: {:?}", key, conditions),
+                    );
+                    is_valid = false;
+                }
+            }
+        }
+
+        if is_valid {
+            Some(parsed)
+        } else {
+            None
+        }
+    }
+
+    fn name_property<T>(
+        &mut self,
+        location: &Location,
+        properties: &HashMap<&str, &RawValue>,
+        key: &str,
+        from_name: fn(&str) -> Option<T>,
+    ) -> Option<T> {
+        let name = self.string_property(location, properties, key)?;
+        let value = from_name(&name);
+
+        if value.is_none() {
+            self.error(
+                location.clone(),
+                format!("Unknown value for property {}: {}", key, name),
+            );
+        }
+
+        value
+    }
+}
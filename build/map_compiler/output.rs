@@ -0,0 +1,116 @@
+// Compiles every .tmx under a directory and writes the results where the game loads them from
+use super::{compile_map_file, write_map_binary, CompiledMap, Diagnostic};
+use std::{collections::HashSet, error::Error, fs, path::Path, result::Result};
+
+// Nothing is written unless every map compiles, so the maps on disk always agree with each other
+pub fn compile_maps(
+    maps_dir: &Path,
+    binary_assets_path: &Path,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<(), Box<dyn Error>> {
+    let mut compiled_maps = vec![];
+
+    compile_maps_in(maps_dir, &mut compiled_maps, diagnostics)?;
+
+    if !diagnostics.is_empty() {
+        return Ok(());
+    }
+
+    for (filename, compiled_map) in &compiled_maps {
+        write_map(binary_assets_path, filename, compiled_map)?;
+    }
+
+    let map_names = compiled_maps
+        .into_iter()
+        .map(|(filename, _)| filename)
+        .collect();
+
+    remove_stale_outputs(binary_assets_path, &map_names)
+}
+
+fn compile_maps_in(
+    maps_dir: &Path,
+    compiled_maps: &mut Vec<(String, CompiledMap)>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<(), Box<dyn Error>> {
+    for file in fs::read_dir(maps_dir)? {
+        let file = file?;
+        let file_type = file.file_type()?;
+
+        if file_type.is_dir() {
+            compile_maps_in(&file.path(), compiled_maps, diagnostics)?;
+        } else if file_type.is_file() {
+            if let Some(ext) = file.path().extension() {
+                if ext == "tmx" {
+                    compile_map(file, compiled_maps, diagnostics)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Map binaries and atlases are named after their .tmx, so anything without one left is from a map
+// that was deleted or renamed
+fn remove_stale_outputs(
+    binary_assets_path: &Path,
+    map_names: &HashSet<String>,
+) -> Result<(), Box<dyn Error>> {
+    for file in fs::read_dir(binary_assets_path)? {
+        let path = file?.path();
+
+        let is_stale = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| !map_names.contains(stem));
+
+        if is_stale && path.is_file() {
+            fs::remove_file(&path)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn compile_map(
+    file: fs::DirEntry,
+    compiled_maps: &mut Vec<(String, CompiledMap)>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<(), Box<dyn Error>> {
+    let filename = file
+        .path()
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| format!("Invalid map filename: {:?}", file.file_name()))?
+        .to_string();
+
+    match compile_map_file(&file.path(), &atlas_path(&filename)) {
+        Ok(compiled_map) => compiled_maps.push((filename, compiled_map)),
+        Err(mut map_diagnostics) => diagnostics.append(&mut map_diagnostics),
+    }
+
+    Ok(())
+}
+
+// Atlases are written next to the map binaries, which the game finds at /bin/maps
+fn atlas_path(filename: &str) -> String {
+    format!("/bin/maps/{}.png", filename)
+}
+
+fn write_map(
+    binary_assets_path: &Path,
+    filename: &str,
+    compiled_map: &CompiledMap,
+) -> Result<(), Box<dyn Error>> {
+    fs::write(
+        binary_assets_path.join(filename),
+        write_map_binary(&compiled_map.tile_map)?,
+    )?;
+
+    compiled_map
+        .atlas
+        .save(binary_assets_path.join(format!("{}.png", filename)))?;
+
+    Ok(())
+}
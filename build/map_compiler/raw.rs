@@ -0,0 +1,135 @@
+// Mirrors the parts of Tiled's map format that the compiler reads
+use std::{fmt, path::PathBuf};
+
+#[derive(Debug)]
+pub struct RawMap {
+    // Infinite maps are sized to fit their chunks, with the top left chunk at the origin
+    pub infinite: bool,
+    pub origin_x: i64,
+    pub origin_y: i64,
+    pub width: u64,
+    pub height: u64,
+    pub tilewidth: u64,
+    pub tileheight: u64,
+    pub properties: Vec<RawProperty>,
+    pub layers: Vec<RawLayer>,
+    pub tilesets: Vec<RawTileSet>,
+}
+
+#[derive(Debug)]
+pub enum RawLayer {
+    TileLayer(RawTileLayer),
+    ObjectGroup(RawObjectGroup),
+}
+
+#[derive(Debug)]
+pub struct RawTileLayer {
+    pub name: String,
+    pub properties: Vec<RawProperty>,
+    pub chunks: Vec<RawChunk>,
+}
+
+// Finite maps have a single chunk covering the whole layer
+#[derive(Debug)]
+pub struct RawChunk {
+    pub x: i64,
+    pub y: i64,
+    pub width: u64,
+    pub height: u64,
+    pub data: Vec<u64>,
+}
+
+#[derive(Debug)]
+pub struct RawObjectGroup {
+    pub name: String,
+    pub objects: Vec<RawObject>,
+}
+
+#[derive(Debug)]
+pub struct RawObject {
+    pub id: u64,
+    pub name: String,
+    pub object_type: String,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub properties: Vec<RawProperty>,
+}
+
+#[derive(Debug)]
+pub struct RawProperty {
+    pub name: String,
+    pub value: RawValue,
+}
+
+#[derive(Debug, Clone)]
+pub enum RawValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl RawValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::Int(value) if *value >= 0 => Some(*value as u64),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RawValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Int(value) => write!(f, "{}", value),
+            Self::Float(value) => write!(f, "{}", value),
+            Self::String(value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RawTileSet {
+    pub firstgid: u64,
+    pub name: String,
+    // Already resolved against the directory of the map or .tsx that declared it
+    pub image: PathBuf,
+    pub imagewidth: u64,
+    pub imageheight: u64,
+    pub tilewidth: u64,
+    pub tileheight: u64,
+    pub tilecount: u64,
+    pub columns: u64,
+    pub tiles: Vec<RawTile>,
+}
+
+#[derive(Debug)]
+pub struct RawTile {
+    pub id: u64,
+    pub tile_type: Option<String>,
+    pub properties: Vec<RawProperty>,
+    pub animation: Option<Vec<RawFrame>>,
+}
+
+#[derive(Debug)]
+pub struct RawFrame {
+    pub tileid: u64,
+    pub duration: u64,
+}
@@ -0,0 +1,672 @@
+// Reads Tiled's .tmx maps and .tsx tilesets into the raw map types
+use super::raw::{
+    RawChunk, RawFrame, RawLayer, RawMap, RawObject, RawObjectGroup, RawProperty, RawTile,
+    RawTileLayer, RawTileSet, RawValue,
+};
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+pub fn read_map(path: &Path) -> Result<RawMap, String> {
+    let tmx = fs::read_to_string(path).map_err(|e| format!("Couldn't read map: {}", e))?;
+    let document =
+        roxmltree::Document::parse(&tmx).map_err(|e| format!("Couldn't parse map: {}", e))?;
+
+    let map = document.root_element();
+    if !map.has_tag_name("map") {
+        return Err(format!(
+            "Expected a <map> element, found <{}>",
+            map.tag_name().name()
+        ));
+    }
+
+    let infinite = map.attribute("infinite") == Some("1");
+    let width = parse_attribute(&map, "width")?;
+    let height = parse_attribute(&map, "height")?;
+
+    let map_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+    let mut tilesets = vec![];
+    for tileset in map.children().filter(|node| node.has_tag_name("tileset")) {
+        tilesets.push(read_tile_set(&tileset, &map_dir)?);
+    }
+
+    let mut layers = vec![];
+    read_layers(&map, width, height, &mut layers)?;
+
+    // The width and height of an infinite map don't mean anything, so fit it to its chunks instead
+    let chunks = layers.iter().flat_map(|layer| match layer {
+        RawLayer::TileLayer(tile_layer) => tile_layer.chunks.iter(),
+        _ => [].iter(),
+    });
+
+    let (origin_x, origin_y, width, height) = if infinite {
+        let (min_x, min_y, max_x, max_y) = chunks.fold(
+            (i64::MAX, i64::MAX, i64::MIN, i64::MIN),
+            |(min_x, min_y, max_x, max_y), chunk| {
+                (
+                    min_x.min(chunk.x),
+                    min_y.min(chunk.y),
+                    max_x.max(chunk.x + chunk.width as i64),
+                    max_y.max(chunk.y + chunk.height as i64),
+                )
+            },
+        );
+
+        if min_x > max_x {
+            return Err("Infinite map has no chunks".to_string());
+        }
+
+        (min_x, min_y, (max_x - min_x) as u64, (max_y - min_y) as u64)
+    } else {
+        (0, 0, width, height)
+    };
+
+    Ok(RawMap {
+        infinite,
+        origin_x,
+        origin_y,
+        width,
+        height,
+        tilewidth: parse_attribute(&map, "tilewidth")?,
+        tileheight: parse_attribute(&map, "tileheight")?,
+        properties: read_properties(&map)?,
+        layers,
+        tilesets,
+    })
+}
+
+fn read_layers(
+    parent: &roxmltree::Node,
+    width: u64,
+    height: u64,
+    layers: &mut Vec<RawLayer>,
+) -> Result<(), String> {
+    for node in parent.children().filter(|node| node.is_element()) {
+        match node.tag_name().name() {
+            "layer" => {
+                let name = attribute_or(&node, "name", "").to_string();
+
+                let data = node
+                    .children()
+                    .find(|child| child.has_tag_name("data"))
+                    .ok_or_else(|| format!("Layer \"{}\" has no <data>", name))?;
+
+                layers.push(RawLayer::TileLayer(RawTileLayer {
+                    chunks: read_chunks(&data, width, height)
+                        .map_err(|e| format!("Layer \"{}\": {}", name, e))?,
+                    properties: read_properties(&node)?,
+                    name,
+                }));
+            }
+            "objectgroup" => {
+                let mut objects = vec![];
+                for object in node.children().filter(|child| child.has_tag_name("object")) {
+                    objects.push(read_object(&object)?);
+                }
+
+                layers.push(RawLayer::ObjectGroup(RawObjectGroup {
+                    name: attribute_or(&node, "name", "").to_string(),
+                    objects,
+                }));
+            }
+            // Groups only organise layers in the editor, so flatten them
+            "group" => read_layers(&node, width, height, layers)?,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn read_chunks(data: &roxmltree::Node, width: u64, height: u64) -> Result<Vec<RawChunk>, String> {
+    let encoding = data.attribute("encoding");
+    let compression = data.attribute("compression");
+
+    let chunks: Vec<roxmltree::Node> = data
+        .children()
+        .filter(|child| child.has_tag_name("chunk"))
+        .collect();
+
+    if chunks.is_empty() {
+        return Ok(vec![RawChunk {
+            x: 0,
+            y: 0,
+            width,
+            height,
+            data: read_tile_ids(data, encoding, compression)?,
+        }]);
+    }
+
+    chunks
+        .iter()
+        .map(|chunk| {
+            Ok(RawChunk {
+                x: parse_attribute(chunk, "x")?,
+                y: parse_attribute(chunk, "y")?,
+                width: parse_attribute(chunk, "width")?,
+                height: parse_attribute(chunk, "height")?,
+                data: read_tile_ids(chunk, encoding, compression)?,
+            })
+        })
+        .collect()
+}
+
+// Chunks are encoded the same way as the <data> they're in
+fn read_tile_ids(
+    data: &roxmltree::Node,
+    encoding: Option<&str>,
+    compression: Option<&str>,
+) -> Result<Vec<u64>, String> {
+    let text = data.text().unwrap_or("").trim();
+
+    match encoding {
+        None => data
+            .children()
+            .filter(|child| child.has_tag_name("tile"))
+            .map(|tile| parse_attribute_or(&tile, "gid", 0))
+            .collect(),
+        Some("csv") => text
+            .split(',')
+            .map(|id| {
+                id.trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid tile id in csv data: {:?}", id.trim()))
+            })
+            .collect(),
+        Some("base64") => {
+            let bytes = base64::decode(text).map_err(|e| format!("Invalid base64 data: {}", e))?;
+
+            let bytes = match compression {
+                None => bytes,
+                Some("zlib") => decompress(flate2::read::ZlibDecoder::new(bytes.as_slice()))?,
+                Some("gzip") => decompress(flate2::read::GzDecoder::new(bytes.as_slice()))?,
+                Some(compression) => {
+                    return Err(format!("Unsupported compression: {}", compression))
+                }
+            };
+
+            if bytes.len() % 4 != 0 {
+                return Err(format!(
+                    "Layer data is {} bytes, which isn't a multiple of 4",
+                    bytes.len()
+                ));
+            }
+
+            Ok(bytes
+                .chunks(4)
+                .map(|id| u32::from_le_bytes([id[0], id[1], id[2], id[3]]) as u64)
+                .collect())
+        }
+        Some(encoding) => Err(format!("Unsupported encoding: {}", encoding)),
+    }
+}
+
+fn decompress(mut decoder: impl Read) -> Result<Vec<u8>, String> {
+    let mut bytes = vec![];
+
+    decoder
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Couldn't decompress data: {}", e))?;
+
+    Ok(bytes)
+}
+
+fn read_tile_set(tileset: &roxmltree::Node, map_dir: &Path) -> Result<RawTileSet, String> {
+    let firstgid = parse_attribute(tileset, "firstgid")?;
+
+    // External tilesets live in their own .tsx, and their paths are relative to it
+    if let Some(source) = tileset.attribute("source") {
+        let tsx_path = map_dir.join(source);
+        let tsx = fs::read_to_string(&tsx_path)
+            .map_err(|e| format!("Couldn't read tileset {}: {}", tsx_path.display(), e))?;
+        let document = roxmltree::Document::parse(&tsx)
+            .map_err(|e| format!("Couldn't parse tileset {}: {}", tsx_path.display(), e))?;
+
+        let tsx_dir = tsx_path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        return read_tile_set_contents(&document.root_element(), firstgid, &tsx_dir)
+            .map_err(|e| format!("Tileset {}: {}", tsx_path.display(), e));
+    }
+
+    read_tile_set_contents(tileset, firstgid, map_dir)
+}
+
+fn read_tile_set_contents(
+    tileset: &roxmltree::Node,
+    firstgid: u64,
+    dir: &Path,
+) -> Result<RawTileSet, String> {
+    let name = attribute_or(tileset, "name", "").to_string();
+
+    let image = tileset
+        .children()
+        .find(|child| child.has_tag_name("image"))
+        .ok_or_else(|| {
+            format!(
+                "Tileset \"{}\" has no image, image collections aren't supported",
+                name
+            )
+        })?;
+
+    let mut tiles = vec![];
+    for tile in tileset
+        .children()
+        .filter(|child| child.has_tag_name("tile"))
+    {
+        let animation = match tile
+            .children()
+            .find(|child| child.has_tag_name("animation"))
+        {
+            Some(animation) => Some(
+                animation
+                    .children()
+                    .filter(|child| child.has_tag_name("frame"))
+                    .map(|frame| {
+                        Ok(RawFrame {
+                            tileid: parse_attribute(&frame, "tileid")?,
+                            duration: parse_attribute(&frame, "duration")?,
+                        })
+                    })
+                    .collect::<Result<Vec<RawFrame>, String>>()?,
+            ),
+            None => None,
+        };
+
+        tiles.push(RawTile {
+            id: parse_attribute(&tile, "id")?,
+            // Newer versions of Tiled renamed type to class
+            tile_type: tile
+                .attribute("type")
+                .or_else(|| tile.attribute("class"))
+                .map(str::to_string),
+            properties: read_properties(&tile)?,
+            animation,
+        });
+    }
+
+    Ok(RawTileSet {
+        firstgid,
+        image: dir.join(PathBuf::from(attribute(&image, "source")?)),
+        imagewidth: parse_attribute(&image, "width")?,
+        imageheight: parse_attribute(&image, "height")?,
+        tilewidth: parse_attribute(tileset, "tilewidth")?,
+        tileheight: parse_attribute(tileset, "tileheight")?,
+        tilecount: parse_attribute(tileset, "tilecount")?,
+        columns: parse_attribute(tileset, "columns")?,
+        tiles,
+        name,
+    })
+}
+
+fn read_object(object: &roxmltree::Node) -> Result<RawObject, String> {
+    Ok(RawObject {
+        id: parse_attribute(object, "id")?,
+        name: attribute_or(object, "name", "").to_string(),
+        object_type: object
+            .attribute("type")
+            .or_else(|| object.attribute("class"))
+            .unwrap_or("")
+            .to_string(),
+        x: parse_attribute(object, "x")?,
+        y: parse_attribute(object, "y")?,
+        width: parse_attribute_or(object, "width", 0.)?,
+        height: parse_attribute_or(object, "height", 0.)?,
+        properties: read_properties(object)?,
+    })
+}
+
+fn read_properties(node: &roxmltree::Node) -> Result<Vec<RawProperty>, String> {
+    let properties = match node
+        .children()
+        .find(|child| child.has_tag_name("properties"))
+    {
+        Some(properties) => properties,
+        None => return Ok(vec![]),
+    };
+
+    properties
+        .children()
+        .filter(|child| child.has_tag_name("property"))
+        .map(|property| {
+            // Multi-line strings are stored as text instead of a value attribute
+            let value = property
+                .attribute("value")
+                .or_else(|| property.text())
+                .unwrap_or("");
+
+            let value = match property.attribute("type") {
+                Some("int") => RawValue::Int(parse_attribute(&property, "value")?),
+                Some("float") => RawValue::Float(parse_attribute(&property, "value")?),
+                Some("bool") => RawValue::Bool(value == "true"),
+                _ => RawValue::String(value.to_string()),
+            };
+
+            Ok(RawProperty {
+                name: attribute(&property, "name")?.to_string(),
+                value,
+            })
+        })
+        .collect()
+}
+
+fn attribute<'a>(node: &roxmltree::Node<'a, '_>, name: &str) -> Result<&'a str, String> {
+    node.attribute(name).ok_or_else(|| {
+        format!(
+            "<{}> on line {} is missing attribute: {}",
+            node.tag_name().name(),
+            node.document().text_pos_at(node.range().start).row,
+            name
+        )
+    })
+}
+
+fn attribute_or<'a>(node: &roxmltree::Node<'a, '_>, name: &str, default: &'a str) -> &'a str {
+    node.attribute(name).unwrap_or(default)
+}
+
+fn parse_attribute<T: FromStr>(node: &roxmltree::Node, name: &str) -> Result<T, String> {
+    let value = attribute(node, name)?;
+
+    value.parse().map_err(|_| {
+        format!(
+            "<{}> on line {} has an invalid {}: {:?}",
+            node.tag_name().name(),
+            node.document().text_pos_at(node.range().start).row,
+            name,
+            value
+        )
+    })
+}
+
+fn parse_attribute_or<T: FromStr>(
+    node: &roxmltree::Node,
+    name: &str,
+    default: T,
+) -> Result<T, String> {
+    match node.attribute(name) {
+        Some(_) => parse_attribute(node, name),
+        None => Ok(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    // Writes the files for a map to their own directory, then reads map.tmx back out of it
+    fn read_fixture(name: &str, files: &[(&str, &str)]) -> RawMap {
+        let dir = std::env::temp_dir().join(format!("tmx_{}_{}", name, std::process::id()));
+
+        for (filename, contents) in files {
+            let path = dir.join(filename);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+
+        let map = read_map(&dir.join("map.tmx"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        map.unwrap()
+    }
+
+    fn map_tmx(infinite: bool, contents: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.4" orientation="orthogonal" width="2" height="2" tilewidth="16" tileheight="16" infinite="{}">
+ {}
+</map>"#,
+            if infinite { 1 } else { 0 },
+            contents
+        )
+    }
+
+    fn tile_layers(map: &RawMap) -> Vec<&RawTileLayer> {
+        map.layers
+            .iter()
+            .filter_map(|layer| match layer {
+                RawLayer::TileLayer(tile_layer) => Some(tile_layer),
+                RawLayer::ObjectGroup(_) => None,
+            })
+            .collect()
+    }
+
+    fn base64_data(ids: &[u32], compression: Option<&str>) -> String {
+        let bytes: Vec<u8> = ids
+            .iter()
+            .flat_map(|id| id.to_le_bytes().to_vec())
+            .collect();
+
+        let bytes = match compression {
+            None => bytes,
+            Some("zlib") => {
+                let mut encoder =
+                    flate2::write::ZlibEncoder::new(vec![], flate2::Compression::default());
+                encoder.write_all(&bytes).unwrap();
+                encoder.finish().unwrap()
+            }
+            Some("gzip") => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+                encoder.write_all(&bytes).unwrap();
+                encoder.finish().unwrap()
+            }
+            Some(compression) => panic!("No fixture for {}", compression),
+        };
+
+        base64::encode(bytes)
+    }
+
+    #[test]
+    fn reads_csv_layer_data() {
+        let map = read_fixture(
+            "csv",
+            &[(
+                "map.tmx",
+                &map_tmx(
+                    false,
+                    r#"<layer id="1" name="Ground" width="2" height="2">
+  <data encoding="csv">
+1,2,
+3,0
+</data>
+ </layer>"#,
+                ),
+            )],
+        );
+
+        assert!(!map.infinite);
+        assert_eq!((map.width, map.height), (2, 2));
+
+        let layers = tile_layers(&map);
+        assert_eq!(layers.len(), 1);
+        assert_eq!(layers[0].name, "Ground");
+        assert_eq!(layers[0].chunks.len(), 1);
+        assert_eq!(layers[0].chunks[0].data, vec![1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn reads_base64_layer_data_with_any_compression() {
+        for compression in [None, Some("zlib"), Some("gzip")].iter() {
+            let data = format!(
+                r#"<layer id="1" name="Ground" width="2" height="2">
+  <data encoding="base64"{}>{}</data>
+ </layer>"#,
+                compression
+                    .map(|compression| format!(r#" compression="{}""#, compression))
+                    .unwrap_or_default(),
+                base64_data(&[4, 0, 2, 0x8000_0001], *compression)
+            );
+
+            let map = read_fixture(
+                compression.unwrap_or("base64"),
+                &[("map.tmx", &map_tmx(false, &data))],
+            );
+
+            // Flip flags in the high bits are kept, they're the compiler's to deal with
+            assert_eq!(
+                tile_layers(&map)[0].chunks[0].data,
+                vec![4, 0, 2, 0x8000_0001],
+                "compression: {:?}",
+                compression
+            );
+        }
+    }
+
+    #[test]
+    fn fits_infinite_maps_to_their_chunks() {
+        let map = read_fixture(
+            "infinite",
+            &[(
+                "map.tmx",
+                &map_tmx(
+                    true,
+                    r#"<layer id="1" name="Ground" width="2" height="2">
+  <data encoding="csv">
+   <chunk x="-2" y="-2" width="2" height="2">
+1,2,
+3,4
+</chunk>
+   <chunk x="0" y="0" width="2" height="2">
+5,6,
+7,8
+</chunk>
+  </data>
+ </layer>"#,
+                ),
+            )],
+        );
+
+        assert!(map.infinite);
+        assert_eq!((map.origin_x, map.origin_y), (-2, -2));
+        assert_eq!((map.width, map.height), (4, 4));
+
+        let chunks = &tile_layers(&map)[0].chunks;
+        assert_eq!(chunks.len(), 2);
+        assert_eq!((chunks[0].x, chunks[0].y), (-2, -2));
+        assert_eq!(chunks[0].data, vec![1, 2, 3, 4]);
+        assert_eq!((chunks[1].x, chunks[1].y), (0, 0));
+        assert_eq!(chunks[1].data, vec![5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn flattens_groups_into_their_layers() {
+        let map = read_fixture(
+            "groups",
+            &[(
+                "map.tmx",
+                &map_tmx(
+                    false,
+                    r#"<layer id="1" name="Ground" width="2" height="2">
+  <data encoding="csv">1,1,1,1</data>
+ </layer>
+ <group id="2" name="Town">
+  <layer id="3" name="Houses" width="2" height="2">
+   <data encoding="csv">0,2,0,2</data>
+  </layer>
+  <group id="4" name="Inside">
+   <objectgroup id="5" name="Doors">
+    <object id="1" name="Front door" type="Door" x="16" y="0" width="16" height="16"/>
+   </objectgroup>
+  </group>
+ </group>"#,
+                ),
+            )],
+        );
+
+        let names: Vec<&str> = map
+            .layers
+            .iter()
+            .map(|layer| match layer {
+                RawLayer::TileLayer(tile_layer) => tile_layer.name.as_str(),
+                RawLayer::ObjectGroup(object_group) => object_group.name.as_str(),
+            })
+            .collect();
+        assert_eq!(names, vec!["Ground", "Houses", "Doors"]);
+
+        match &map.layers[2] {
+            RawLayer::ObjectGroup(object_group) => {
+                assert_eq!(object_group.objects.len(), 1);
+                assert_eq!(object_group.objects[0].name, "Front door");
+                assert_eq!(object_group.objects[0].object_type, "Door");
+                assert_eq!(
+                    (object_group.objects[0].x, object_group.objects[0].y),
+                    (16., 0.)
+                );
+            }
+            layer => panic!("Expected the Doors object group, found {:?}", layer),
+        }
+    }
+
+    #[test]
+    fn reads_external_tilesets_relative_to_their_tsx() {
+        let map = read_fixture(
+            "tsx",
+            &[
+                (
+                    "map.tmx",
+                    &map_tmx(
+                        false,
+                        r#"<tileset firstgid="1" source="tilesets/town.tsx"/>
+ <layer id="1" name="Ground" width="2" height="2">
+  <data encoding="csv">1,2,3,4</data>
+ </layer>"#,
+                    ),
+                ),
+                (
+                    "tilesets/town.tsx",
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+<tileset version="1.4" name="Town" tilewidth="16" tileheight="16" tilecount="4" columns="2">
+ <image source="../images/town.png" width="32" height="32"/>
+ <tile id="1" type="Water">
+  <properties>
+   <property name="blocks_movement" type="bool" value="true"/>
+   <property name="footstep_sound" value="splash"/>
+  </properties>
+  <animation>
+   <frame tileid="1" duration="200"/>
+   <frame tileid="3" duration="300"/>
+  </animation>
+ </tile>
+</tileset>"#,
+                ),
+            ],
+        );
+
+        assert_eq!(map.tilesets.len(), 1);
+
+        let tileset = &map.tilesets[0];
+        assert_eq!(tileset.firstgid, 1);
+        assert_eq!(tileset.name, "Town");
+        assert!(tileset.image.ends_with(
+            Path::new("tilesets")
+                .join("..")
+                .join("images")
+                .join("town.png")
+        ));
+        assert_eq!((tileset.imagewidth, tileset.imageheight), (32, 32));
+        assert_eq!((tileset.tilecount, tileset.columns), (4, 2));
+
+        assert_eq!(tileset.tiles.len(), 1);
+
+        let tile = &tileset.tiles[0];
+        assert_eq!(tile.id, 1);
+        assert_eq!(tile.tile_type.as_deref(), Some("Water"));
+        assert_eq!(tile.properties.len(), 2);
+        assert_eq!(tile.properties[0].value.as_bool(), Some(true));
+        assert_eq!(tile.properties[1].value.as_str(), Some("splash"));
+
+        let frames = tile.animation.as_ref().unwrap();
+        assert_eq!(
+            frames
+                .iter()
+                .map(|frame| (frame.tileid, frame.duration))
+                .collect::<Vec<_>>(),
+            vec![(1, 200), (3, 300)]
+        );
+    }
+}
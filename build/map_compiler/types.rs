@@ -0,0 +1,219 @@
+// These must be kept in sync with the runtime types in src/game/maps
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TileMap {
+    pub width: u64,
+    pub height: u64,
+    pub atlas: Atlas,
+    pub tiles: Vec<Tile>,
+    pub layers: Vec<TileLayer>,
+    pub chunks: Vec<TileChunk>,
+    pub objects: Vec<MapObject>,
+    pub connections: Vec<MapConnection>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Atlas {
+    pub image_filename: String,
+    pub width: u64,
+    pub height: u64,
+    pub uvs: Vec<[f32; 4]>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TileLayer {
+    pub name: String,
+    pub draw_order: DrawOrder,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TileChunk {
+    pub x: u64,
+    pub y: u64,
+    // One list of tile ids per layer, which is empty when the layer has nothing in this chunk
+    pub layers: Vec<Vec<Option<u64>>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum DrawOrder {
+    Below,
+    Above,
+    Sorted,
+}
+
+impl DrawOrder {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "below" => Some(Self::Below),
+            "above" => Some(Self::Above),
+            "sorted" => Some(Self::Sorted),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum TileProperty {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Tile {
+    pub id: u64,
+    pub properties: BTreeMap<String, TileProperty>,
+    pub animation: Option<Vec<TileAnimationFrame>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TileAnimationFrame {
+    pub tile_id: u64,
+    pub duration_millis: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MapObject {
+    pub id: u64,
+    pub name: String,
+    pub x: u64,
+    pub y: u64,
+    pub width: u64,
+    pub height: u64,
+    pub kind: MapObjectKind,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum MapObjectKind {
+    Door {
+        door_id: u64,
+        to_map: MapName,
+        to_id: u64,
+        facing: GameDirection,
+    },
+    Sign {
+        text: String,
+    },
+    Npc {
+        entity_name: EntityName,
+        facing: Option<GameDirection>,
+    },
+    Trigger {
+        event: TriggerEvent,
+        function: String,
+        conditions: Vec<StateCondition>,
+        once: bool,
+    },
+    Boulder,
+    PressurePlate {
+        state: StateName,
+    },
+    Gate {
+        conditions: Vec<StateCondition>,
+    },
+    Pickup {
+        item: ItemId,
+        quantity: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum TriggerEvent {
+    Step,
+    Enter,
+    Leave,
+}
+
+impl TriggerEvent {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Step" => Some(Self::Step),
+            "Enter" => Some(Self::Enter),
+            "Leave" => Some(Self::Leave),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StateCondition {
+    pub state: StateName,
+    pub in_map: bool,
+    pub is_set: bool,
+}
+
+// The connected map is lined up against this map's edge in the direction, shifted along that
+// edge by offset tiles
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MapConnection {
+    pub direction: GameDirection,
+    pub map: MapName,
+    pub offset: i64,
+}
+
+// Any id is accepted here, the game looks it up in assets/maps.toml when it's used
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MapName(String);
+
+impl MapName {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(Self(name.to_string()))
+    }
+}
+
+// Any id is accepted here too, the game looks it up in assets/items.toml when it's picked up
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ItemId(String);
+
+impl ItemId {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(Self(name.to_string()))
+    }
+}
+
+// Entities are looked up in assets/maps.toml, and states don't need declaring anywhere
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EntityName(String);
+
+impl EntityName {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(Self(name.to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StateName(String);
+
+impl StateName {
+    // Conditions are split on commas, so a stray one would otherwise make an empty state
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.is_empty() {
+            None
+        } else {
+            Some(Self(name.to_string()))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum GameDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl GameDirection {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Up" => Some(Self::Up),
+            "Down" => Some(Self::Down),
+            "Left" => Some(Self::Left),
+            "Right" => Some(Self::Right),
+            _ => None,
+        }
+    }
+}
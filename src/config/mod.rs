@@ -18,9 +18,23 @@ pub const SETTINGS_FILE_PATH: &str = "/settings.conf";
 
 pub const APPLICATION_ICON_FILE_PATH: &str = "/icon.png";
 
+pub const MAP_REGISTRY_FILE_PATH: &str = "/maps.toml";
+
+pub const ITEM_REGISTRY_FILE_PATH: &str = "/items.toml";
+
+pub const QUEST_REGISTRY_FILE_PATH: &str = "/quests.toml";
+
 pub const WALK_SECONDS_PER_TILE: f32 = 0.25;
+pub const RUN_SECONDS_PER_TILE: f32 = 0.125;
 pub const WAIT_AFTER_TURN_BEFORE_MOVE: f32 = 0.175;
 
+// How high the top of a hop over a ledge is, in tiles
+pub const LEDGE_HOP_HEIGHT: f32 = 0.5;
+
+// NPCs that move on their own wait somewhere in between these before each move
+pub const NPC_WAIT_SECONDS_MIN: f32 = 1.;
+pub const NPC_WAIT_SECONDS_MAX: f32 = 3.;
+
 // 16x16 tile sizes
 pub const TILE_PIXELS_SIZE_USIZE: usize = 16;
 pub const TILE_PIXELS_SIZE_F32: f32 = TILE_PIXELS_SIZE_USIZE as f32;
@@ -48,3 +62,8 @@ pub const ENTITY_SPRITE_SHEET_IDX_WALK_DOWN: usize = 4;
 pub const ENTITY_SPRITE_SHEET_IDX_WALK_RIGHT: usize = 5;
 pub const ENTITY_SPRITE_SHEET_IDX_WALK_UP: usize = 6;
 pub const ENTITY_SPRITE_SHEET_IDX_WALK_LEFT: usize = 7;
+
+pub const ENTITY_SPRITE_SHEET_IDX_RUN_DOWN: usize = 8;
+pub const ENTITY_SPRITE_SHEET_IDX_RUN_RIGHT: usize = 9;
+pub const ENTITY_SPRITE_SHEET_IDX_RUN_UP: usize = 10;
+pub const ENTITY_SPRITE_SHEET_IDX_RUN_LEFT: usize = 11;
@@ -7,13 +7,15 @@ use std::sync::Arc;
 pub struct Drawable {
     pub drawable: Arc<dyn ggez::graphics::Drawable + Sync + Send>,
     pub draw_params: ggez::graphics::DrawParam,
+    // Tiles above its position to draw at, like when hopping over a ledge
+    pub offset_y: f32,
 }
 
 impl std::fmt::Debug for Drawable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(&format!(
-            "Drawable {{ draw_params: {:?}, drawable: ... }}",
-            self.draw_params
+            "Drawable {{ draw_params: {:?}, offset_y: {}, drawable: ... }}",
+            self.draw_params, self.offset_y
         ))
     }
 }
@@ -1,6 +1,6 @@
-use super::input::types::GameDirection;
+use super::{config, input::types::GameDirection, SpriteSheet};
 use serde_derive::{Deserialize, Serialize};
-use specs::{Component, VecStorage};
+use specs::{Component, Entity, VecStorage, WorldExt};
 use specs_derive::Component;
 
 #[derive(Component, Debug, Serialize, Deserialize)]
@@ -8,3 +8,21 @@ use specs_derive::Component;
 pub struct FacingDirection {
     pub direction: GameDirection,
 }
+
+impl FacingDirection {
+    // Turns the entity on the spot, standing idle in the new direction
+    pub fn turn(world: &specs::World, entity: Entity, direction: GameDirection) {
+        if let Some(facing_direction) = world.write_storage::<FacingDirection>().get_mut(entity) {
+            facing_direction.direction = direction;
+        }
+
+        if let Some(sprite_sheet) = world.write_storage::<SpriteSheet>().get_mut(entity) {
+            sprite_sheet.set_row(match direction {
+                GameDirection::Down => config::ENTITY_SPRITE_SHEET_IDX_IDLE_DOWN,
+                GameDirection::Left => config::ENTITY_SPRITE_SHEET_IDX_IDLE_LEFT,
+                GameDirection::Up => config::ENTITY_SPRITE_SHEET_IDX_IDLE_UP,
+                GameDirection::Right => config::ENTITY_SPRITE_SHEET_IDX_IDLE_RIGHT,
+            });
+        }
+    }
+}
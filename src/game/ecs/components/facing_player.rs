@@ -0,0 +1,58 @@
+use super::{input::types::GameDirection, FacingDirection, GameResult, NpcBehaviour, NpcMovement};
+use specs::{Component, Entity, VecStorage, WorldExt};
+use specs_derive::Component;
+
+// Turned to face the player while they interact, and held there until the interaction is over
+#[derive(Component, Debug)]
+#[storage(VecStorage)]
+pub struct FacingPlayer {
+    pub previous_direction: GameDirection,
+}
+
+impl FacingPlayer {
+    pub fn face(
+        world: &mut specs::World,
+        entity: Entity,
+        player_direction: GameDirection,
+    ) -> GameResult {
+        let previous_direction = match world.read_storage::<FacingDirection>().get(entity) {
+            Some(facing_direction) => facing_direction.direction,
+            None => return Ok(()),
+        };
+
+        FacingDirection::turn(world, entity, player_direction.opposite());
+
+        world
+            .write_storage::<FacingPlayer>()
+            .insert(entity, FacingPlayer { previous_direction })
+            .map_err(|e| {
+                ggez::GameError::CustomError(format!(
+                    "Couldn't turn {:?} to face the player: {}",
+                    entity, e
+                ))
+            })?;
+
+        Ok(())
+    }
+
+    pub fn stop(world: &mut specs::World, entity: Entity) {
+        let facing_player = match world.write_storage::<FacingPlayer>().remove(entity) {
+            Some(facing_player) => facing_player,
+            None => return,
+        };
+
+        // Entities moving on their own just carry on from here
+        let is_moving =
+            world
+                .read_storage::<NpcMovement>()
+                .get(entity)
+                .map_or(false, |npc_movement| match npc_movement.behaviour {
+                    NpcBehaviour::Stand => false,
+                    _ => true,
+                });
+
+        if !is_moving {
+            FacingDirection::turn(world, entity, facing_player.previous_direction);
+        }
+    }
+}
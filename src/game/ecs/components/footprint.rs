@@ -0,0 +1,28 @@
+use specs::{Component, VecStorage};
+use specs_derive::Component;
+
+// The tiles an entity takes up, starting from its position in the top left. Entities without one
+// only take up the tile they're on
+#[derive(Component, Debug, Clone)]
+#[storage(VecStorage)]
+pub struct Footprint {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Default for Footprint {
+    fn default() -> Self {
+        Self {
+            width: 1,
+            height: 1,
+        }
+    }
+}
+
+impl Footprint {
+    pub fn positions(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+
+        (y..y + self.height).flat_map(move |y| (x..x + width).map(move |x| (x, y)))
+    }
+}
@@ -0,0 +1,20 @@
+use super::{MapName, SaveData, StateCondition};
+use specs::{Component, VecStorage};
+use specs_derive::Component;
+
+// Takes up its tiles until its conditions are met, and only closes again once they're clear
+#[derive(Component, Debug, Clone)]
+#[storage(VecStorage)]
+pub struct Gate {
+    pub x: usize,
+    pub y: usize,
+    pub conditions: Vec<StateCondition>,
+}
+
+impl Gate {
+    pub fn is_open(&self, save_data: &SaveData, map: &MapName) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.is_met(save_data, map))
+    }
+}
@@ -0,0 +1,17 @@
+use specs::{Component, VecStorage};
+use specs_derive::Component;
+
+// Where an entity's Drawable image was loaded from
+#[derive(Component, Debug)]
+#[storage(VecStorage)]
+pub struct ImageFile {
+    pub filename: String,
+}
+
+impl ImageFile {
+    pub fn new(filename: &str) -> Self {
+        Self {
+            filename: filename.to_string(),
+        }
+    }
+}
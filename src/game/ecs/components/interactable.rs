@@ -1,9 +1,35 @@
-use super::scenes::types::SceneBuilder;
+use super::{
+    scenes::{types::SceneBuilder, CutsceneScene},
+    scripting::ScriptEngine,
+};
 use specs::{Component, Entity, VecStorage};
 use specs_derive::Component;
+use std::{cell::RefCell, rc::Rc};
 
 #[derive(Component)]
 #[storage(VecStorage)]
 pub struct Interactable {
     pub handler: Box<dyn Fn(Entity, Entity) -> Option<SceneBuilder> + Send + Sync>,
 }
+
+impl Interactable {
+    // Plays out whatever the script's interact() function asks for
+    pub fn from_script(filename: &str) -> Self {
+        let filename = filename.to_string();
+
+        Self {
+            handler: Box::new(move |_, _| {
+                let filename = filename.clone();
+
+                let scene_builder: SceneBuilder = Box::new(move |game_state, ctx| {
+                    let commands = ScriptEngine::run(&game_state.world, ctx, &filename, "interact");
+
+                    let scene = CutsceneScene::new(game_state, commands);
+                    Ok(Rc::new(RefCell::new(scene)))
+                });
+
+                Some(scene_builder)
+            }),
+        }
+    }
+}
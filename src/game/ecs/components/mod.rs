@@ -2,86 +2,188 @@ mod current_position;
 mod door;
 mod drawable;
 mod facing_direction;
+mod facing_player;
+mod footprint;
+mod gate;
 mod id;
+mod image_file;
 mod interactable;
 mod is_static;
+mod npc_movement;
 mod player;
+mod pressure_plate;
+mod pushable;
+mod scripted;
 mod sprite_sheet;
 mod target_position;
 mod timer;
+mod trigger;
 
 pub use current_position::CurrentPosition;
 pub use door::Door;
 pub use drawable::Drawable;
 pub use facing_direction::FacingDirection;
+pub use facing_player::FacingPlayer;
+pub use footprint::Footprint;
+pub use gate::Gate;
 pub use id::Id;
+pub use image_file::ImageFile;
 pub use interactable::Interactable;
 pub use is_static::IsStatic;
+pub use npc_movement::{NpcBehaviour, NpcMovement};
 pub use player::Player;
+pub use pressure_plate::PressurePlate;
+pub use pushable::Pushable;
+pub use scripted::Scripted;
 pub use sprite_sheet::{SpriteRow, SpriteSheet};
 pub use target_position::TargetPosition;
 pub use timer::Timer;
+pub use trigger::{StateCondition, Trigger, TriggerEvent};
 
 use super::super::{
+    config,
+    ecs::resources::{MapRegistry, PlayerMovementRequest},
     error::types::GameResult,
     game_state::GameState,
     input::{self, types::GameDirection},
     maps,
     save::{MetaSaveData, SaveData},
-    scenes,
+    scenes, scripting,
 };
 use serde::{Deserialize, Serialize};
-use specs::{Component, VecStorage};
+use specs::{Builder, Component, Entity, VecStorage, WorldExt};
 use specs_derive::Component;
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, sync::Arc};
 
+// Entities are looked up by this name in the map registry, which has their dialog, movement and
+// script
 #[derive(Debug, Component, Serialize, Deserialize, Hash, PartialEq, Eq, Clone)]
 #[storage(VecStorage)]
-pub enum EntityName {
-    WiseOldMan,
-}
+pub struct EntityName(String);
 
 impl EntityName {
-    pub fn new_entity(game_state: &mut GameState, ctx: &mut ggez::Context) {
-        // TODO: find and move, or insert
+    pub fn new(name: &str) -> Self {
+        Self(name.to_string())
+    }
+
+    pub fn new_entity(
+        &self,
+        game_state: &mut GameState,
+        ctx: &mut ggez::Context,
+        position: (usize, usize),
+        direction: GameDirection,
+    ) -> GameResult<Entity> {
+        let entity = game_state
+            .world
+            .create_entity()
+            .with(self.clone())
+            .with(Id::new(&self.0))
+            .with(Drawable {
+                drawable: Arc::new(ggez::graphics::Mesh::new_rectangle(
+                    ctx,
+                    ggez::graphics::DrawMode::fill(),
+                    ggez::graphics::Rect::new(
+                        0.,
+                        config::TILE_PIXELS_SIZE_F32 - 24.,
+                        config::TILE_PIXELS_SIZE_F32,
+                        24.,
+                    ),
+                    ggez::graphics::Color::from_rgb(20, 50, 150),
+                )?),
+                draw_params: ggez::graphics::DrawParam::default(),
+                offset_y: 0.,
+            })
+            .with(CurrentPosition {
+                x: position.0 as f32,
+                y: position.1 as f32,
+            })
+            .with(TargetPosition {
+                x: position.0,
+                y: position.1,
+                from_x: position.0 as isize,
+                from_y: position.1 as isize,
+                is_moving: false,
+                is_hopping: false,
+            })
+            .with(Timer {
+                duration: config::WALK_SECONDS_PER_TILE,
+                repeating: true,
+                elapsed: 0.0,
+                finished: true,
+                should_tick: false,
+            })
+            .with(SpriteSheet::new(vec![
+                SpriteRow::new(1), // IDLE DOWN
+                SpriteRow::new(1), // IDLE RIGHT
+                SpriteRow::new(1), // IDLE UP
+                SpriteRow::new(1), // IDLE LEFT
+                SpriteRow::new(1), // WALK DOWN
+                SpriteRow::new(1), // WALK RIGHT
+                SpriteRow::new(1), // WALK UP
+                SpriteRow::new(1), // WALK LEFT
+            ]))
+            .with(FacingDirection { direction })
+            .with(Interactable {
+                handler: Box::new(|player_entity, target_entity| {
+                    let scene_builder: scenes::types::SceneBuilder = Box::new(move |_, _| {
+                        let scene = scenes::TextBoxScene::new(
+                            target_entity,
+                            &format!("{:?} says hello to: {:?}", target_entity, player_entity),
+                        );
+                        Ok(Rc::new(RefCell::new(scene)))
+                    });
+
+                    Some(scene_builder)
+                }),
+            })
+            .build();
+
+        Ok(entity)
     }
 }
 
+impl std::fmt::Display for EntityName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+// Maps are looked up by this id in the map registry
 #[derive(Debug, Component, Serialize, Deserialize, Hash, PartialEq, Eq, Clone)]
 #[storage(VecStorage)]
-pub enum MapName {
-    PalletTown,
-    Varrock,
-}
+pub struct MapName(String);
 
 impl MapName {
+    pub fn new(id: &str) -> Self {
+        Self(id.to_string())
+    }
+
     pub fn scene_builder(&self) -> scenes::types::SceneBuilder {
-        match self {
-            Self::PalletTown => Box::new(|game_state, ctx| {
-                let scene = scenes::PalletTownOverworldScene::new(game_state, ctx)?;
-                Ok(Rc::new(RefCell::new(scene)))
-            }),
-            Self::Varrock => Box::new(|game_state, ctx| {
-                let scene = scenes::VarrockOverworldScene::new(game_state, ctx)?;
-                Ok(Rc::new(RefCell::new(scene)))
-            }),
-        }
-    }
-
-    pub fn scene_builder_from_door(
-        self,
-        door_id: usize,
-    ) -> GameResult<scenes::types::SceneBuilder> {
-        let (position, direction) = self.get_door_position(door_id).ok_or_else(|| {
-            ggez::GameError::CustomError(format!(
-                "No door found for door_id [{}] for map: {:#?}",
-                door_id, self
-            ))
-        })?;
+        let map_name = self.clone();
+
+        Box::new(move |game_state, ctx| {
+            let scene = scenes::MapScene::new(game_state, ctx, map_name.clone())?;
+            Ok(Rc::new(RefCell::new(scene)))
+        })
+    }
 
+    pub fn scene_builder_from_door(self, door_id: usize) -> scenes::types::SceneBuilder {
         let map_scene_builder: scenes::types::SceneBuilder = self.scene_builder();
 
-        Ok(Box::new(move |game_state: &mut GameState, ctx| {
+        Box::new(move |game_state: &mut GameState, ctx| {
+            let tile_map_definition = {
+                let map_registry = game_state.world.fetch::<MapRegistry>();
+                maps::TileMapDefinition::load(ctx, &map_registry, &self)?
+            };
+
+            let (position, direction) =
+                tile_map_definition.find_door(door_id).ok_or_else(|| {
+                    ggez::GameError::CustomError(format!(
+                        "No door found for door_id [{}] for map: {}",
+                        door_id, self
+                    ))
+                })?;
+
             {
                 let mut save_data = game_state.world.fetch_mut::<SaveData>();
                 save_data.player.map = self.clone();
@@ -98,43 +200,156 @@ impl MapName {
             }
 
             map_scene_builder(game_state, ctx)
-        }))
+        })
     }
 
-    pub fn get_door_position(&self, door_id: usize) -> Option<((usize, usize), GameDirection)> {
-        match self {
-            Self::PalletTown => match door_id {
-                0 => Some(((19, 19), GameDirection::Up)),
-                _ => None,
-            },
-            Self::Varrock => match door_id {
-                0 => Some(((13, 0), GameDirection::Down)),
-                _ => None,
-            },
-        }
+    pub fn scene_builder_from_warp(
+        self,
+        position: (usize, usize),
+        direction: GameDirection,
+    ) -> scenes::types::SceneBuilder {
+        let map_scene_builder: scenes::types::SceneBuilder = self.scene_builder();
+
+        Box::new(move |game_state: &mut GameState, ctx| {
+            {
+                let mut save_data = game_state.world.fetch_mut::<SaveData>();
+                save_data.player.map = self.clone();
+                save_data.player.position.x = position.0;
+                save_data.player.position.y = position.1;
+                save_data.player.position.facing = Some(direction);
+            }
+            {
+                let mut meta_save_data = game_state.world.fetch_mut::<MetaSaveData>();
+                meta_save_data.current_map = self.clone();
+            }
+
+            map_scene_builder(game_state, ctx)
+        })
+    }
+
+    pub fn scene_builder_from_connection(
+        self,
+        position: (usize, usize),
+        direction: GameDirection,
+    ) -> scenes::types::SceneBuilder {
+        let map_scene_builder: scenes::types::SceneBuilder = self.scene_builder();
+
+        Box::new(move |game_state: &mut GameState, ctx| {
+            {
+                let mut save_data = game_state.world.fetch_mut::<SaveData>();
+                save_data.player.map = self.clone();
+                save_data.player.position.x = position.0;
+                save_data.player.position.y = position.1;
+                save_data.player.position.facing = Some(direction);
+            }
+            {
+                let mut meta_save_data = game_state.world.fetch_mut::<MetaSaveData>();
+                meta_save_data.current_map = self.clone();
+            }
+
+            // Map scenes stop the player when they load, but walking into a connected map
+            // should carry on with whatever is still held down
+            let player_movement_request =
+                (*game_state.world.fetch::<PlayerMovementRequest>()).clone();
+            let input_state = game_state.input_state.clone();
+
+            let scene = map_scene_builder(game_state, ctx)?;
+
+            *game_state.world.fetch_mut::<PlayerMovementRequest>() = player_movement_request;
+            game_state.input_state = input_state;
+
+            maps::step_into_map(game_state, direction)?;
+
+            Ok(scene)
+        })
+    }
+}
+
+impl std::fmt::Display for MapName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+// Items are looked up by this id in the item registry
+#[derive(Debug, Component, Serialize, Deserialize, Hash, PartialEq, Eq, Clone)]
+#[storage(VecStorage)]
+pub struct ItemId(String);
+
+impl ItemId {
+    pub fn new(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl std::fmt::Display for ItemId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
     }
 }
 
+// Quests and their tasks are looked up by these ids in the quest registry
 #[derive(Debug, Component, Serialize, Deserialize, Hash, PartialEq, Eq, Clone)]
 #[storage(VecStorage)]
-pub enum QuestName {
-    TestQuest,
+pub struct QuestName(String);
+
+impl QuestName {
+    pub fn new(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl std::fmt::Display for QuestName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
 #[derive(Debug, Component, Serialize, Deserialize, Hash, PartialEq, Eq, Clone)]
 #[storage(VecStorage)]
-pub enum TaskName {
-    TestTask,
+pub struct TaskName(String);
+
+impl TaskName {
+    pub fn new(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl std::fmt::Display for TaskName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
 #[derive(Debug, Component, Serialize, Deserialize, Hash, PartialEq, Eq, Clone)]
 #[storage(VecStorage)]
-pub enum ChoiceName {
-    TestChoice,
+pub struct ChoiceName(String);
+
+impl ChoiceName {
+    pub fn new(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl std::fmt::Display for ChoiceName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
+// States are set by scripts and pressure plates, so any id can be one
 #[derive(Debug, Component, Serialize, Deserialize, Hash, PartialEq, Eq, Clone)]
 #[storage(VecStorage)]
-pub enum StateName {
-    TestState,
+pub struct StateName(String);
+
+impl StateName {
+    pub fn new(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl std::fmt::Display for StateName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
 }
@@ -0,0 +1,36 @@
+use super::Timer;
+use serde::Deserialize;
+use specs::{Component, VecStorage};
+use specs_derive::Component;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum NpcBehaviour {
+    Stand,
+    LookAround,
+    // Steps around randomly, staying within radius tiles of where the NPC spawned
+    Wander { radius: usize },
+    // Walks to each point in order, then back to the first
+    Patrol { route: Vec<(usize, usize)> },
+}
+
+#[derive(Component, Debug)]
+#[storage(VecStorage)]
+pub struct NpcMovement {
+    pub behaviour: NpcBehaviour,
+    pub origin: (usize, usize),
+    pub next_waypoint: usize,
+    // Counts down until the NPC does something again
+    pub wait: Timer,
+}
+
+impl NpcMovement {
+    pub fn new(behaviour: NpcBehaviour, origin: (usize, usize)) -> Self {
+        Self {
+            behaviour,
+            origin,
+            next_waypoint: 0,
+            wait: Timer::from_seconds(0., false),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use super::StateName;
+use specs::{Component, VecStorage};
+use specs_derive::Component;
+
+// Sets a map state while something pushable is resting on it. Plates don't take up tiles, so
+// anything can walk over them
+#[derive(Component, Debug, Clone)]
+#[storage(VecStorage)]
+pub struct PressurePlate {
+    pub x: usize,
+    pub y: usize,
+    pub state: StateName,
+}
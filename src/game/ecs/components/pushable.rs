@@ -0,0 +1,10 @@
+use specs::{Component, VecStorage};
+use specs_derive::Component;
+
+// Moved a tile by the player walking into it. Pushable entities only ever take up one tile, and
+// the id is the map object's, which is what their position is saved under
+#[derive(Component, Debug, Clone)]
+#[storage(VecStorage)]
+pub struct Pushable {
+    pub id: usize,
+}
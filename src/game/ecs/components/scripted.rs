@@ -0,0 +1,7 @@
+use specs::{Component, NullStorage};
+use specs_derive::Component;
+
+// Moved by a cutscene, so it doesn't also move on its own until the cutscene is over
+#[derive(Default, Component, Debug)]
+#[storage(NullStorage)]
+pub struct Scripted;
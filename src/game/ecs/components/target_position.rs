@@ -1,4 +1,4 @@
-use super::{CurrentPosition, Timer};
+use super::{super::super::config, CurrentPosition, Timer};
 use serde_derive::{Deserialize, Serialize};
 use specs::{Component, VecStorage};
 use specs_derive::Component;
@@ -9,14 +9,19 @@ pub struct TargetPosition {
     pub x: usize,
     pub y: usize,
 
+    // The step into a connected map starts just past its edge
     #[serde(skip)]
-    pub from_x: usize,
+    pub from_x: isize,
 
     #[serde(skip)]
-    pub from_y: usize,
+    pub from_y: isize,
 
     #[serde(skip)]
     pub is_moving: bool,
+
+    // Hopping over a ledge, which lands two tiles away instead of one
+    #[serde(skip)]
+    pub is_hopping: bool,
 }
 
 impl TargetPosition {
@@ -33,4 +38,13 @@ impl TargetPosition {
             y: from_y + (y - from_y) * percent,
         }
     }
+
+    // Hops arc up and back down over the time it takes to move
+    pub fn get_hop_height(&self, timer: &Timer) -> f32 {
+        if self.is_hopping {
+            (timer.percent() * std::f32::consts::PI).sin() * config::LEDGE_HOP_HEIGHT
+        } else {
+            0.
+        }
+    }
 }
@@ -0,0 +1,86 @@
+use super::{MapName, SaveData, StateName};
+use serde::{Deserialize, Serialize};
+use specs::{Component, VecStorage};
+use specs_derive::Component;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerEvent {
+    // Finishing a step anywhere in the region
+    Step,
+    // Stepping into the region from outside of it
+    Enter,
+    // Stepping out of the region
+    Leave,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StateCondition {
+    pub state: StateName,
+    // Otherwise it's one of the world's states
+    pub in_map: bool,
+    pub is_set: bool,
+}
+
+impl StateCondition {
+    pub fn is_met(&self, save_data: &SaveData, map: &MapName) -> bool {
+        let is_set = if self.in_map {
+            save_data.maps.get(map).map_or(false, |map_definition| {
+                map_definition.states.contains(&self.state)
+            })
+        } else {
+            save_data.world.states.contains(&self.state)
+        };
+
+        is_set == self.is_set
+    }
+}
+
+// A region of the map that runs a function in the map's script when the player walks through it.
+// Triggers don't take up tiles, so anything can walk over them
+#[derive(Component, Debug, Clone)]
+#[storage(VecStorage)]
+pub struct Trigger {
+    pub id: usize,
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    pub event: TriggerEvent,
+    pub function: String,
+    pub conditions: Vec<StateCondition>,
+    pub once: bool,
+}
+
+impl Trigger {
+    // Positions can be off the edge of the map, where the player walks in from a connected map
+    pub fn contains(&self, (x, y): (isize, isize)) -> bool {
+        x >= self.x as isize
+            && y >= self.y as isize
+            && x < (self.x + self.width) as isize
+            && y < (self.y + self.height) as isize
+    }
+
+    pub fn fires_on(&self, from: (isize, isize), to: (isize, isize)) -> bool {
+        match self.event {
+            TriggerEvent::Step => self.contains(to),
+            TriggerEvent::Enter => self.contains(to) && !self.contains(from),
+            TriggerEvent::Leave => self.contains(from) && !self.contains(to),
+        }
+    }
+
+    pub fn can_fire(&self, save_data: &SaveData, map: &MapName) -> bool {
+        let map_definition = save_data.maps.get(map);
+
+        if self.once
+            && map_definition.map_or(false, |map_definition| {
+                map_definition.triggered.contains(&self.id)
+            })
+        {
+            return false;
+        }
+
+        self.conditions
+            .iter()
+            .all(|condition| condition.is_met(save_data, map))
+    }
+}
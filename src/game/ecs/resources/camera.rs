@@ -6,4 +6,7 @@ pub struct Camera {
     pub right: usize,
     pub top: usize,
     pub bottom: usize,
+    // How far a cutscene has moved the camera away from the player, in tiles
+    pub pan_x: f32,
+    pub pan_y: f32,
 }
@@ -0,0 +1,15 @@
+use super::{components::MapName, input::types::GameDirection};
+
+#[derive(Default)]
+pub struct ConnectionRequest {
+    pub requesting: Option<MapConnectionStep>,
+}
+
+// A step off the edge of the map, into a tile of the map connected there
+#[derive(Debug, Clone)]
+pub struct MapConnectionStep {
+    pub to_map: MapName,
+    pub x: usize,
+    pub y: usize,
+    pub direction: GameDirection,
+}
@@ -0,0 +1,70 @@
+use super::{
+    components::{EntityName, ItemId, MapName, QuestName, StateName, TaskName},
+    input::types::GameDirection,
+    save::TaskStatus,
+};
+use serde::Deserialize;
+
+// Who a cutscene command is about
+#[derive(Deserialize, Debug, Clone)]
+pub enum CutsceneTarget {
+    Player,
+    Entity(EntityName),
+}
+
+// Each command finishes before the next one starts
+#[derive(Deserialize, Debug, Clone)]
+pub enum CutsceneCommand {
+    Move {
+        target: CutsceneTarget,
+        direction: GameDirection,
+        tiles: usize,
+    },
+    Face {
+        target: CutsceneTarget,
+        direction: GameDirection,
+    },
+    ShowText {
+        text: String,
+    },
+    Wait {
+        seconds: f32,
+    },
+    SetState {
+        state: StateName,
+        on: bool,
+    },
+    SetTask {
+        quest: QuestName,
+        task: TaskName,
+        status: TaskStatus,
+    },
+    // Whatever doesn't fit in the bag is lost
+    GiveItem {
+        item: ItemId,
+        quantity: usize,
+    },
+    TakeItem {
+        item: ItemId,
+        quantity: usize,
+    },
+    // Moves the camera away from the player by x, y tiles. Panning back to 0, 0 follows the
+    // player again
+    PanCamera {
+        x: f32,
+        y: f32,
+        seconds: f32,
+    },
+    // Anything after this carries on in the new map
+    Warp {
+        map: MapName,
+        x: usize,
+        y: usize,
+        facing: GameDirection,
+    },
+}
+
+#[derive(Default)]
+pub struct CutsceneRequest {
+    pub requesting: Option<Vec<CutsceneCommand>>,
+}
@@ -0,0 +1,88 @@
+use super::{components::ItemId, config, error::types::GameResult};
+use serde::Deserialize;
+use std::{collections::HashMap, io::Read};
+
+// Which pocket of the bag an item goes in
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemCategory {
+    General,
+    Medicine,
+    // Can't be tossed
+    Key,
+}
+
+impl ItemCategory {
+    pub const ALL: [ItemCategory; 3] = [Self::General, Self::Medicine, Self::Key];
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ItemDefinition {
+    pub id: ItemId,
+    pub name: String,
+    pub description: String,
+    pub category: ItemCategory,
+    // The most of the item the bag holds at once
+    pub max_stack: usize,
+    // Runs on_use() when the item is used from the bag. Items without one can't be used
+    pub script: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ItemRegistryFile {
+    items: Vec<ItemDefinition>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ItemRegistry {
+    pub items: HashMap<ItemId, ItemDefinition>,
+}
+
+impl ItemRegistry {
+    pub fn load(ctx: &mut ggez::Context) -> GameResult<Self> {
+        let mut file = ctx
+            .filesystem
+            .find_vfs(&ctx.filesystem.assets_path)
+            .ok_or_else(|| {
+                ggez::GameError::FilesystemError("Couldn't find asset filesystem:".to_string())
+            })?
+            .open(&std::path::PathBuf::from(config::ITEM_REGISTRY_FILE_PATH))?;
+
+        let mut encoded = String::new();
+        file.read_to_string(&mut encoded)?;
+
+        let registry_file: ItemRegistryFile = toml::from_str(&encoded)?;
+
+        let mut items = HashMap::new();
+        for item in registry_file.items {
+            if items.contains_key(&item.id) {
+                return Err(ggez::GameError::ConfigError(format!(
+                    "Item {} is in {} more than once",
+                    item.id,
+                    config::ITEM_REGISTRY_FILE_PATH
+                )));
+            }
+
+            if item.max_stack == 0 {
+                return Err(ggez::GameError::ConfigError(format!(
+                    "Item {} in {} has a max_stack of 0",
+                    item.id,
+                    config::ITEM_REGISTRY_FILE_PATH
+                )));
+            }
+
+            items.insert(item.id.clone(), item);
+        }
+
+        Ok(Self { items })
+    }
+
+    pub fn get(&self, item: &ItemId) -> GameResult<&ItemDefinition> {
+        self.items.get(item).ok_or_else(|| {
+            ggez::GameError::CustomError(format!(
+                "Item {} isn't in {}",
+                item,
+                config::ITEM_REGISTRY_FILE_PATH
+            ))
+        })
+    }
+}
@@ -0,0 +1,122 @@
+use super::{
+    components::{EntityName, MapName, NpcBehaviour},
+    config,
+    error::types::GameResult,
+    input::types::GameDirection,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, io::Read};
+
+// Where new saves put the player
+#[derive(Deserialize, Debug, Clone)]
+pub struct MapStart {
+    pub map: MapName,
+    pub x: usize,
+    pub y: usize,
+    pub facing: Option<GameDirection>,
+}
+
+// How an NPC behaves. Where it spawns is its Npc object in the Tiled map
+#[derive(Deserialize, Debug, Clone)]
+pub struct MapEntity {
+    pub name: EntityName,
+    pub dialog_id: usize,
+    // Entities without any stay wherever the save has them
+    pub movement: Option<NpcBehaviour>,
+    // Runs interact() when the player talks to the entity, instead of its built-in dialog
+    pub script: Option<String>,
+}
+
+// Doors, signs and NPC spawns are objects in the tile map, so they're authored in Tiled instead
+#[derive(Deserialize, Debug, Clone)]
+pub struct MapManifest {
+    pub id: MapName,
+    pub name: String,
+    pub tile_map: String,
+    // Checked against the assets when the registry loads, so a typo doesn't go unnoticed
+    pub music: Option<String>,
+    // Runs on_enter() whenever the player comes into the map
+    pub script: Option<String>,
+    #[serde(default)]
+    pub entities: Vec<MapEntity>,
+}
+
+#[derive(Deserialize)]
+struct MapRegistryFile {
+    start: MapStart,
+    maps: Vec<MapManifest>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MapRegistry {
+    pub start: MapStart,
+    pub maps: HashMap<MapName, MapManifest>,
+}
+
+impl MapRegistry {
+    pub fn load(ctx: &mut ggez::Context) -> GameResult<Self> {
+        let vfs = ctx
+            .filesystem
+            .find_vfs(&ctx.filesystem.assets_path)
+            .ok_or_else(|| {
+                ggez::GameError::FilesystemError("Couldn't find asset filesystem:".to_string())
+            })?;
+
+        let mut file = vfs.open(&std::path::PathBuf::from(config::MAP_REGISTRY_FILE_PATH))?;
+
+        let mut encoded = String::new();
+        file.read_to_string(&mut encoded)?;
+
+        let registry_file: MapRegistryFile = toml::from_str(&encoded)?;
+
+        let mut maps = HashMap::new();
+        for manifest in registry_file.maps {
+            if maps.contains_key(&manifest.id) {
+                return Err(ggez::GameError::ConfigError(format!(
+                    "Map {} is in {} more than once",
+                    manifest.id,
+                    config::MAP_REGISTRY_FILE_PATH
+                )));
+            }
+
+            if let Some(music) = &manifest.music {
+                if !vfs.exists(&std::path::PathBuf::from(music)) {
+                    return Err(ggez::GameError::ConfigError(format!(
+                        "Map {} plays {}, which isn't in the assets",
+                        manifest.id, music
+                    )));
+                }
+            }
+
+            maps.insert(manifest.id.clone(), manifest);
+        }
+
+        let registry = Self {
+            start: registry_file.start,
+            maps,
+        };
+
+        registry.get(&registry.start.map)?;
+
+        Ok(registry)
+    }
+
+    pub fn has_entity(&self, entity_name: &EntityName) -> bool {
+        self.maps.values().any(|manifest| {
+            manifest
+                .entities
+                .iter()
+                .any(|entity| entity.name == *entity_name)
+        })
+    }
+
+    pub fn get(&self, map_name: &MapName) -> GameResult<&MapManifest> {
+        self.maps.get(map_name).ok_or_else(|| {
+            ggez::GameError::CustomError(format!(
+                "Map {} isn't in {}",
+                map_name,
+                config::MAP_REGISTRY_FILE_PATH
+            ))
+        })
+    }
+}
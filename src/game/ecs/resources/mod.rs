@@ -1,20 +1,38 @@
 mod camera;
 mod camera_bounds;
+mod connection_request;
+mod cutscene_request;
 mod delta_time;
 mod door_request;
+mod item_registry;
+mod map_registry;
 mod player_movement_request;
+mod quest_registry;
+mod quest_updates;
+mod rng;
 mod should_update;
 mod tile_map;
+mod trigger_request;
+mod warp_request;
 
 pub use camera::Camera;
 pub use camera_bounds::CameraBounds;
+pub use connection_request::{ConnectionRequest, MapConnectionStep};
+pub use cutscene_request::{CutsceneCommand, CutsceneRequest, CutsceneTarget};
 pub use delta_time::DeltaTime;
 pub use door_request::DoorRequest;
-pub use player_movement_request::PlayerMovementRequest;
+pub use item_registry::{ItemCategory, ItemRegistry};
+pub use map_registry::MapRegistry;
+pub use player_movement_request::{MovementMode, PlayerMovementRequest};
+pub use quest_registry::{QuestRegistry, TaskUpdate};
+pub use quest_updates::QuestUpdates;
+pub use rng::Rng;
 pub use should_update::ShouldUpdateBackgroundTiles;
-pub use tile_map::{Frame, Tile, TileMap};
+pub use tile_map::{ConnectedMap, Frame, TileGrid, TileMap, TileMapLayer};
+pub use trigger_request::TriggerRequest;
+pub use warp_request::{MapWarp, WarpRequest};
 
 use super::{
-    super::{input, maps},
+    super::{config, error, input, maps, save},
     components,
 };
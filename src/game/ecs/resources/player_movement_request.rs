@@ -1,8 +1,45 @@
-use super::input::types::GameDirection;
+use super::{config, input::types::GameDirection};
 
-#[derive(Default)]
+// How fast the player gets around, and how they look doing it. Only switched between steps, so a
+// step always finishes at the speed it started at
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MovementMode {
+    Walk,
+    Run,
+}
+
+impl Default for MovementMode {
+    fn default() -> Self {
+        Self::Walk
+    }
+}
+
+impl MovementMode {
+    pub fn seconds_per_tile(&self) -> f32 {
+        match self {
+            Self::Walk => config::WALK_SECONDS_PER_TILE,
+            Self::Run => config::RUN_SECONDS_PER_TILE,
+        }
+    }
+
+    pub fn sprite_sheet_row(&self, direction: &GameDirection) -> usize {
+        match (self, direction) {
+            (Self::Walk, GameDirection::Down) => config::ENTITY_SPRITE_SHEET_IDX_WALK_DOWN,
+            (Self::Walk, GameDirection::Right) => config::ENTITY_SPRITE_SHEET_IDX_WALK_RIGHT,
+            (Self::Walk, GameDirection::Up) => config::ENTITY_SPRITE_SHEET_IDX_WALK_UP,
+            (Self::Walk, GameDirection::Left) => config::ENTITY_SPRITE_SHEET_IDX_WALK_LEFT,
+            (Self::Run, GameDirection::Down) => config::ENTITY_SPRITE_SHEET_IDX_RUN_DOWN,
+            (Self::Run, GameDirection::Right) => config::ENTITY_SPRITE_SHEET_IDX_RUN_RIGHT,
+            (Self::Run, GameDirection::Up) => config::ENTITY_SPRITE_SHEET_IDX_RUN_UP,
+            (Self::Run, GameDirection::Left) => config::ENTITY_SPRITE_SHEET_IDX_RUN_LEFT,
+        }
+    }
+}
+
+#[derive(Default, Clone)]
 pub struct PlayerMovementRequest {
     pub last_requested_direction: Option<GameDirection>,
     pub last_requested_x_direction: Option<GameDirection>,
     pub last_requested_y_direction: Option<GameDirection>,
+    pub movement_mode: MovementMode,
 }
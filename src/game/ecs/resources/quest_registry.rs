@@ -0,0 +1,369 @@
+use super::{
+    components::{EntityName, ItemId, MapName, QuestName, StateName, TaskName},
+    config,
+    error::types::GameResult,
+    save::{QuestDefinition, SaveData, TaskStatus},
+    ItemRegistry, MapRegistry,
+};
+use serde::Deserialize;
+use std::io::Read;
+
+#[derive(Deserialize, Debug, Clone)]
+pub enum QuestCondition {
+    // One of the world's states is set
+    Flag {
+        state: StateName,
+    },
+    MapFlag {
+        map: MapName,
+        state: StateName,
+    },
+    // At least this many are in the bag
+    Item {
+        item: ItemId,
+        quantity: usize,
+    },
+    // Only talking to them while the task is active counts
+    TalkedTo {
+        entity: EntityName,
+    },
+    Task {
+        quest: QuestName,
+        task: TaskName,
+        status: TaskStatus,
+    },
+}
+
+impl QuestCondition {
+    // The quest and task are the ones the condition belongs to
+    fn is_met(&self, save_data: &SaveData, quest: &QuestName, task: &TaskName) -> bool {
+        match self {
+            Self::Flag { state } => save_data.world.states.contains(state),
+            Self::MapFlag { map, state } => {
+                save_data.maps.get(map).map_or(false, |map_definition| {
+                    map_definition.states.contains(state)
+                })
+            }
+            Self::Item { item, quantity } => save_data.player.inventory.quantity(item) >= *quantity,
+            Self::TalkedTo { entity } => save_data
+                .player
+                .journal
+                .get(quest)
+                .and_then(|quest_definition| quest_definition.talked_to.get(task))
+                .map_or(false, |talked_to| talked_to.contains(entity)),
+            Self::Task {
+                quest,
+                task,
+                status,
+            } => save_data.player.task_status(quest, task) == *status,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TaskInfo {
+    pub id: TaskName,
+    pub description: String,
+    // All of these have to be met before the task can start
+    #[serde(default)]
+    pub requires: Vec<QuestCondition>,
+    // Starts as soon as it can, instead of waiting for game code or a script to start it
+    #[serde(default)]
+    pub auto_start: bool,
+    // Tasks without any of these only finish when game code or a script says so
+    #[serde(default)]
+    pub complete_when: Vec<QuestCondition>,
+    #[serde(default)]
+    pub fail_when: Vec<QuestCondition>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct QuestInfo {
+    pub id: QuestName,
+    pub name: String,
+    // In the order they're listed in the journal
+    pub tasks: Vec<TaskInfo>,
+}
+
+impl QuestInfo {
+    pub fn get(&self, task: &TaskName) -> GameResult<&TaskInfo> {
+        self.tasks
+            .iter()
+            .find(|task_info| task_info.id == *task)
+            .ok_or_else(|| {
+                ggez::GameError::CustomError(format!(
+                    "Task {} isn't in quest {} in {}",
+                    task,
+                    self.id,
+                    config::QUEST_REGISTRY_FILE_PATH
+                ))
+            })
+    }
+}
+
+// A task's status changing, for the player to be told about
+#[derive(Debug, Clone)]
+pub struct TaskUpdate {
+    pub quest: QuestName,
+    pub task: TaskName,
+    pub from: TaskStatus,
+    pub to: TaskStatus,
+}
+
+#[derive(Deserialize)]
+struct QuestRegistryFile {
+    quests: Vec<QuestInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QuestRegistry {
+    // In the order they're listed in the journal
+    pub quests: Vec<QuestInfo>,
+}
+
+impl QuestRegistry {
+    pub fn load(
+        ctx: &mut ggez::Context,
+        map_registry: &MapRegistry,
+        item_registry: &ItemRegistry,
+    ) -> GameResult<Self> {
+        let mut file = ctx
+            .filesystem
+            .find_vfs(&ctx.filesystem.assets_path)
+            .ok_or_else(|| {
+                ggez::GameError::FilesystemError("Couldn't find asset filesystem:".to_string())
+            })?
+            .open(&std::path::PathBuf::from(config::QUEST_REGISTRY_FILE_PATH))?;
+
+        let mut encoded = String::new();
+        file.read_to_string(&mut encoded)?;
+
+        let registry_file: QuestRegistryFile = toml::from_str(&encoded)?;
+        let registry = Self {
+            quests: registry_file.quests,
+        };
+
+        for (idx, quest_info) in registry.quests.iter().enumerate() {
+            if registry.quests[..idx]
+                .iter()
+                .any(|other| other.id == quest_info.id)
+            {
+                return Err(ggez::GameError::ConfigError(format!(
+                    "Quest {} is in {} more than once",
+                    quest_info.id,
+                    config::QUEST_REGISTRY_FILE_PATH
+                )));
+            }
+
+            for (idx, task_info) in quest_info.tasks.iter().enumerate() {
+                if quest_info.tasks[..idx]
+                    .iter()
+                    .any(|other| other.id == task_info.id)
+                {
+                    return Err(ggez::GameError::ConfigError(format!(
+                        "Task {} is in quest {} more than once",
+                        task_info.id, quest_info.id
+                    )));
+                }
+
+                // Conditions have to point at real tasks, maps, items and entities
+                for condition in task_info
+                    .requires
+                    .iter()
+                    .chain(&task_info.complete_when)
+                    .chain(&task_info.fail_when)
+                {
+                    let checked = match condition {
+                        QuestCondition::Flag { .. } => Ok(()),
+                        QuestCondition::MapFlag { map, .. } => map_registry.get(map).map(|_| ()),
+                        QuestCondition::Item { item, .. } => item_registry.get(item).map(|_| ()),
+                        QuestCondition::TalkedTo { entity } => {
+                            if map_registry.has_entity(entity) {
+                                Ok(())
+                            } else {
+                                Err(ggez::GameError::CustomError(format!(
+                                    "Entity {} isn't in any map in {}",
+                                    entity,
+                                    config::MAP_REGISTRY_FILE_PATH
+                                )))
+                            }
+                        }
+                        QuestCondition::Task { quest, task, .. } => registry
+                            .get(quest)
+                            .and_then(|other| other.get(task))
+                            .map(|_| ()),
+                    };
+
+                    if let Err(e) = checked {
+                        return Err(ggez::GameError::ConfigError(format!(
+                            "Task {} in quest {} has a bad condition: {}",
+                            task_info.id, quest_info.id, e
+                        )));
+                    }
+                }
+            }
+        }
+
+        Ok(registry)
+    }
+
+    pub fn get(&self, quest: &QuestName) -> GameResult<&QuestInfo> {
+        self.quests
+            .iter()
+            .find(|quest_info| quest_info.id == *quest)
+            .ok_or_else(|| {
+                ggez::GameError::CustomError(format!(
+                    "Quest {} isn't in {}",
+                    quest,
+                    config::QUEST_REGISTRY_FILE_PATH
+                ))
+            })
+    }
+
+    pub fn can_start(
+        &self,
+        save_data: &SaveData,
+        quest: &QuestName,
+        task: &TaskName,
+    ) -> GameResult<bool> {
+        let task_info = self.get(quest)?.get(task)?;
+
+        Ok(is_waiting(&save_data.player.task_status(quest, task))
+            && task_info
+                .requires
+                .iter()
+                .all(|condition| condition.is_met(save_data, quest, task)))
+    }
+
+    // Tasks can't be started before their prerequisites are met, but can be moved anywhere else
+    pub fn set_task(
+        &self,
+        save_data: &mut SaveData,
+        quest: &QuestName,
+        task: &TaskName,
+        status: TaskStatus,
+    ) -> GameResult<Option<TaskUpdate>> {
+        self.get(quest)?.get(task)?;
+
+        let from = save_data.player.task_status(quest, task);
+
+        if from == status {
+            return Ok(None);
+        }
+
+        if status == TaskStatus::Active
+            && is_waiting(&from)
+            && !self.can_start(save_data, quest, task)?
+        {
+            return Err(ggez::GameError::CustomError(format!(
+                "Task {} in quest {} can't start before its prerequisites are met",
+                task, quest
+            )));
+        }
+
+        save_data
+            .player
+            .journal
+            .entry(quest.clone())
+            .or_insert_with(QuestDefinition::new)
+            .tasks
+            .insert(task.clone(), status.clone());
+
+        Ok(Some(TaskUpdate {
+            quest: quest.clone(),
+            task: task.clone(),
+            from,
+            to: status,
+        }))
+    }
+
+    // Counts towards every active task that's waiting on talking to them
+    pub fn talked_to(&self, save_data: &mut SaveData, entity: &EntityName) {
+        for quest_info in &self.quests {
+            for task_info in &quest_info.tasks {
+                if save_data.player.task_status(&quest_info.id, &task_info.id) != TaskStatus::Active
+                {
+                    continue;
+                }
+
+                let is_waiting_on_entity = task_info
+                    .complete_when
+                    .iter()
+                    .chain(&task_info.fail_when)
+                    .any(|condition| match condition {
+                        QuestCondition::TalkedTo { entity: other } => other == entity,
+                        _ => false,
+                    });
+
+                if is_waiting_on_entity {
+                    save_data
+                        .player
+                        .journal
+                        .entry(quest_info.id.clone())
+                        .or_insert_with(QuestDefinition::new)
+                        .talked_to
+                        .entry(task_info.id.clone())
+                        .or_default()
+                        .insert(entity.clone());
+                }
+            }
+        }
+    }
+
+    // Starts, completes and fails whatever tasks are ready to, until nothing else changes since
+    // one task finishing can let the next one start
+    pub fn advance(&self, save_data: &mut SaveData) -> Vec<TaskUpdate> {
+        let mut updates = vec![];
+
+        loop {
+            let mut changed = false;
+
+            for quest_info in &self.quests {
+                for task_info in &quest_info.tasks {
+                    let quest = &quest_info.id;
+                    let task = &task_info.id;
+
+                    let all_met = |conditions: &Vec<QuestCondition>| {
+                        !conditions.is_empty()
+                            && conditions
+                                .iter()
+                                .all(|condition| condition.is_met(save_data, quest, task))
+                    };
+
+                    let status = match save_data.player.task_status(quest, task) {
+                        TaskStatus::Active if all_met(&task_info.fail_when) => TaskStatus::Failed,
+                        TaskStatus::Active if all_met(&task_info.complete_when) => {
+                            TaskStatus::Complete
+                        }
+                        ref from
+                            if task_info.auto_start
+                                && is_waiting(from)
+                                && task_info
+                                    .requires
+                                    .iter()
+                                    .all(|condition| condition.is_met(save_data, quest, task)) =>
+                        {
+                            TaskStatus::Active
+                        }
+                        _ => continue,
+                    };
+
+                    // Prerequisites were just checked, so this can't fail
+                    if let Ok(Some(update)) = self.set_task(save_data, quest, task, status) {
+                        updates.push(update);
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                return updates;
+            }
+        }
+    }
+}
+
+// Tasks that haven't started yet
+fn is_waiting(status: &TaskStatus) -> bool {
+    *status == TaskStatus::Unknown || *status == TaskStatus::NotStarted
+}
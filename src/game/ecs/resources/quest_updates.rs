@@ -0,0 +1,7 @@
+use super::TaskUpdate;
+
+// Task changes the player hasn't been told about yet
+#[derive(Default)]
+pub struct QuestUpdates {
+    pub updates: Vec<TaskUpdate>,
+}
@@ -0,0 +1,38 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Xorshift, which is plenty for things like picking where NPCs wander to next
+pub struct Rng {
+    state: u64,
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(0);
+
+        // Xorshift never leaves a state of zero
+        Self { state: seed | 1 }
+    }
+}
+
+impl Rng {
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    // Between 0 and below, not including below
+    pub fn below(&mut self, below: usize) -> usize {
+        (self.next_u64() % below as u64) as usize
+    }
+
+    pub fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        let percent = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+
+        min + (max - min) * percent
+    }
+}
@@ -1,48 +1,314 @@
 use super::{
-    components::{Drawable, MapName},
-    maps::TileType,
+    components::{Drawable, Footprint, MapName},
+    error::types::GameResult,
+    input::types::GameDirection,
+    maps::{ChunkCache, DrawOrder, TileProperty},
 };
-
-#[derive(Default, Debug)]
-pub struct Tile {
-    pub tile_type: Option<TileType>,
-    pub entity: Option<specs::Entity>,
-}
+use specs::Entity;
+use std::collections::{BTreeMap, HashMap};
 
 pub struct Frame {
     pub idx: usize,
     pub tile_ids: Vec<usize>,
+    // Seconds each tile is shown for, lined up with tile_ids
+    pub durations: Vec<f32>,
+    pub elapsed: f32,
+}
+
+impl Frame {
+    // Starting partway through lets tiles with the same timing animate out of step
+    pub fn new(tile_ids: Vec<usize>, durations: Vec<f32>, offset: f32) -> Self {
+        Self {
+            idx: 0,
+            tile_ids,
+            durations,
+            elapsed: offset,
+        }
+    }
+
+    pub fn tile_id(&self) -> usize {
+        self.tile_ids[self.idx]
+    }
+
+    // Returns whether the shown tile changed
+    pub fn advance(&mut self, secs: f32) -> bool {
+        let total_duration: f32 = self.durations.iter().sum();
+        if total_duration <= 0. {
+            return false;
+        }
+
+        let previous_idx = self.idx;
+
+        self.elapsed = (self.elapsed + secs) % total_duration;
+
+        // Skip over every frame that finished since the last advance
+        while self.elapsed >= self.durations[self.idx] {
+            self.elapsed -= self.durations[self.idx];
+            self.idx = (self.idx + 1) % self.tile_ids.len();
+        }
+
+        self.idx != previous_idx
+    }
+}
+
+pub struct TileMapLayer {
+    pub name: String,
+    pub draw_order: DrawOrder,
+    atlas_image: ggez::graphics::Image,
+    // Sorted layers get a batch per row so entities can be drawn between them, but only for the
+    // rows being drawn. Other layers only ever use row 0
+    spritesheets: BTreeMap<usize, ggez::graphics::spritebatch::SpriteBatch>,
+}
+
+impl TileMapLayer {
+    pub fn new(name: String, draw_order: DrawOrder, atlas_image: ggez::graphics::Image) -> Self {
+        Self {
+            name,
+            draw_order,
+            atlas_image,
+            spritesheets: BTreeMap::new(),
+        }
+    }
+
+    fn row(&self, y: usize) -> usize {
+        match self.draw_order {
+            DrawOrder::Sorted => y,
+            _ => 0,
+        }
+    }
+
+    pub fn spritesheet(&self, y: usize) -> Option<&ggez::graphics::spritebatch::SpriteBatch> {
+        self.spritesheets.get(&self.row(y))
+    }
+
+    pub fn spritesheet_mut(&mut self, y: usize) -> &mut ggez::graphics::spritebatch::SpriteBatch {
+        let row = self.row(y);
+        let atlas_image = &self.atlas_image;
+
+        self.spritesheets
+            .entry(row)
+            .or_insert_with(|| ggez::graphics::spritebatch::SpriteBatch::new(atlas_image.clone()))
+    }
+
+    // From the top row down
+    pub fn spritesheets(&self) -> impl Iterator<Item = &ggez::graphics::spritebatch::SpriteBatch> {
+        self.spritesheets.values()
+    }
+
+    // Batches for rows outside of top to bottom are dropped, the rest are kept to be refilled
+    pub fn clear(&mut self, top: usize, bottom: usize) {
+        if self.draw_order == DrawOrder::Sorted {
+            self.spritesheets.retain(|y, _| top <= *y && *y < bottom);
+        }
+
+        for spritesheet in self.spritesheets.values_mut() {
+            spritesheet.clear();
+        }
+    }
+}
+
+// A map joined onto an edge of the current one, which is drawn past that edge
+pub struct ConnectedMap {
+    pub map_name: MapName,
+    // Where the connected map's top left tile is, in the current map's tiles
+    pub origin_x: isize,
+    pub origin_y: isize,
+    pub tile_map: TileMap,
+}
+
+// What can be walked on and who's standing where. Tiles are only known in the chunks around the
+// camera, and the rest of the map is treated as blocked until the camera comes near it, while
+// entities are kept track of everywhere. It doesn't need a graphics context, so it can be built
+// and searched without one
+#[derive(Debug)]
+pub struct TileGrid {
+    width: usize,
+    height: usize,
+    chunks: ChunkCache,
+    // Keyed by tile id, for the tiles that have any
+    tile_properties: HashMap<usize, HashMap<String, TileProperty>>,
+    entities: HashMap<(usize, usize), Entity>,
+}
+
+impl TileGrid {
+    pub fn new(
+        width: usize,
+        height: usize,
+        chunks: ChunkCache,
+        tile_properties: HashMap<usize, HashMap<String, TileProperty>>,
+        entities: HashMap<(usize, usize), Entity>,
+    ) -> Self {
+        Self {
+            width,
+            height,
+            chunks,
+            tile_properties,
+            entities,
+        }
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    // Loads the chunks touching the area in tiles, and drops the ones far away from it
+    pub fn load_around(
+        &mut self,
+        left: usize,
+        top: usize,
+        right: usize,
+        bottom: usize,
+    ) -> GameResult {
+        self.chunks.load_around(left, top, right, bottom)
+    }
+
+    #[cfg(test)]
+    pub fn load_all(&mut self) -> GameResult {
+        self.chunks.load_all()
+    }
+
+    pub fn tile_id(&self, layer: usize, x: usize, y: usize) -> Option<usize> {
+        self.chunks.tile_id(layer, x, y)
+    }
+
+    pub fn entity(&self, x: usize, y: usize) -> Option<Entity> {
+        self.entities.get(&(x, y)).copied()
+    }
+
+    // Returns whoever was there before
+    pub fn set_entity(&mut self, x: usize, y: usize, entity: Option<Entity>) -> Option<Entity> {
+        match entity {
+            Some(entity) => self.entities.insert((x, y), entity),
+            None => self.entities.remove(&(x, y)),
+        }
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = (&(usize, usize), &Entity)> {
+        self.entities.iter()
+    }
+
+    // Properties on higher layers override the same properties on lower layers
+    pub fn tile_property(&self, x: usize, y: usize, key: &str) -> Option<&TileProperty> {
+        self.chunks
+            .tile_ids(x, y)
+            .rev()
+            .find_map(|tile_id| self.tile_properties.get(&tile_id)?.get(key))
+    }
+
+    pub fn blocks_movement(&self, x: usize, y: usize) -> bool {
+        !self.chunks.is_resident(x, y)
+            || self
+                .tile_property(x, y, "blocks_movement")
+                .and_then(TileProperty::as_bool)
+                .unwrap_or(false)
+    }
+
+    pub fn is_slippery(&self, x: usize, y: usize) -> bool {
+        self.tile_property(x, y, "slippery")
+            .and_then(TileProperty::as_bool)
+            .unwrap_or(false)
+    }
+
+    pub fn footstep_sound(&self, x: usize, y: usize) -> Option<&str> {
+        self.tile_property(x, y, "footstep_sound")
+            .and_then(TileProperty::as_str)
+    }
+
+    pub fn encounter_zone(&self, x: usize, y: usize) -> Option<&str> {
+        self.tile_property(x, y, "encounter_zone")
+            .and_then(TileProperty::as_str)
+    }
+
+    pub fn ledge_direction(&self, x: usize, y: usize) -> Option<GameDirection> {
+        match self
+            .tile_property(x, y, "ledge_direction")
+            .and_then(TileProperty::as_str)?
+        {
+            "Up" => Some(GameDirection::Up),
+            "Down" => Some(GameDirection::Down),
+            "Left" => Some(GameDirection::Left),
+            "Right" => Some(GameDirection::Right),
+            _ => None,
+        }
+    }
+
+    // Whether the entity fits with its top left at x, y. Tiles it's already in don't get in its
+    // own way
+    pub fn has_room_for(&self, entity: Entity, x: isize, y: isize, footprint: &Footprint) -> bool {
+        let (width, height) = self.dimensions();
+
+        if x < 0
+            || y < 0
+            || x as usize + footprint.width > width
+            || y as usize + footprint.height > height
+        {
+            return false;
+        }
+
+        footprint.positions(x as usize, y as usize).all(|(x, y)| {
+            !self.blocks_movement(x, y)
+                && self
+                    .entity(x, y)
+                    .map_or(true, |tile_entity| tile_entity == entity)
+        })
+    }
+
+    pub fn move_entity(
+        &mut self,
+        entity: Entity,
+        from: (usize, usize),
+        to: (usize, usize),
+        footprint: &Footprint,
+    ) {
+        for (x, y) in footprint.positions(from.0, from.1) {
+            if self.entity(x, y) == Some(entity) {
+                self.set_entity(x, y, None);
+            }
+        }
+
+        for (x, y) in footprint.positions(to.0, to.1) {
+            self.set_entity(x, y, Some(entity));
+        }
+    }
 }
 
 pub struct TileMap {
-    pub tiles: Vec<Vec<Tile>>,
-    pub background_indices: Vec<Option<usize>>,
-    pub overlay_indices: Vec<Option<usize>>,
-    pub background_animation: Vec<Frame>,
-    pub overlay_animation: Vec<Frame>,
-    pub background: ggez::graphics::spritebatch::SpriteBatch,
-    pub background_width: usize,
-    pub background_height: usize,
-    pub overlay: ggez::graphics::spritebatch::SpriteBatch,
-    pub overlay_width: usize,
-    pub overlay_height: usize,
+    pub grid: TileGrid,
+    pub layers: Vec<TileMapLayer>,
+    // Where each tile id is in the atlas all of the layers draw from
+    pub uvs: Vec<ggez::graphics::Rect>,
+    // Keyed by the animated tile's id
+    pub animation: HashMap<usize, Frame>,
+    pub connected_maps: Vec<ConnectedMap>,
     pub spritesheet_param: ggez::graphics::DrawParam,
-    pub to_draw: Vec<Drawable>,
+    pub to_draw: Vec<(usize, Drawable)>,
 }
 
 impl TileMap {
-    pub fn dimensions(&self) -> (usize, usize) {
-        let height = self.tiles.len();
-        let width = if height == 0 { 0 } else { self.tiles[0].len() };
+    // The connected map covering a position past the edge of this one, and where that position
+    // is inside of it
+    pub fn connected_map_at(&self, x: isize, y: isize) -> Option<(&ConnectedMap, (usize, usize))> {
+        self.connected_maps.iter().find_map(|connected_map| {
+            let (width, height) = connected_map.tile_map.grid.dimensions();
 
-        (width, height)
-    }
+            let local_x = x - connected_map.origin_x;
+            let local_y = y - connected_map.origin_y;
+
+            if local_x < 0 || local_y < 0 || local_x >= width as isize || local_y >= height as isize
+            {
+                return None;
+            }
 
-    pub fn get_tile(&self, x: usize, y: usize) -> &Tile {
-        &self.tiles[y][x]
+            Some((connected_map, (local_x as usize, local_y as usize)))
+        })
     }
 
-    pub fn get_tile_mut(&mut self, x: usize, y: usize) -> &mut Tile {
-        &mut self.tiles[y][x]
+    pub fn layers_by_draw_order(
+        &self,
+        draw_order: DrawOrder,
+    ) -> impl Iterator<Item = &TileMapLayer> {
+        self.layers
+            .iter()
+            .filter(move |layer| layer.draw_order == draw_order)
     }
 }
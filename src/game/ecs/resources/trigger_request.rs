@@ -0,0 +1,5 @@
+// Functions in the current map's script, from every trigger the player set off at once
+#[derive(Default)]
+pub struct TriggerRequest {
+    pub requesting: Vec<String>,
+}
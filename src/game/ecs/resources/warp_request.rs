@@ -0,0 +1,15 @@
+use super::{components::MapName, input::types::GameDirection};
+
+#[derive(Default)]
+pub struct WarpRequest {
+    pub requesting: Option<MapWarp>,
+}
+
+// Puts the player straight into a tile of a map, without needing a door there
+#[derive(Debug, Clone)]
+pub struct MapWarp {
+    pub to_map: MapName,
+    pub x: usize,
+    pub y: usize,
+    pub facing: GameDirection,
+}
@@ -0,0 +1,29 @@
+use super::{
+    resources::{QuestRegistry, QuestUpdates},
+    save::SaveData,
+};
+
+#[derive(Debug)]
+pub struct AdvanceQuestsSystem;
+
+impl<'a> specs::System<'a> for AdvanceQuestsSystem {
+    type SystemData = (
+        Option<specs::Write<'a, SaveData>>,
+        Option<specs::Read<'a, QuestRegistry>>,
+        specs::Write<'a, QuestUpdates>,
+    );
+
+    #[tracing::instrument(
+        skip(save_data_r, quest_registry_r, quest_updates_r),
+        name = "AdvanceQuestsSystem"
+    )]
+    fn run(&mut self, (save_data_r, quest_registry_r, mut quest_updates_r): Self::SystemData) {
+        let (mut save_data_r, quest_registry_r) = match (save_data_r, quest_registry_r) {
+            (Some(save_data_r), Some(quest_registry_r)) => (save_data_r, quest_registry_r),
+            _ => return,
+        };
+
+        let updates = quest_registry_r.advance(&mut save_data_r);
+        quest_updates_r.updates.extend(updates);
+    }
+}
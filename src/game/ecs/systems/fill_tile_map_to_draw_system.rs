@@ -2,6 +2,7 @@ use super::{
     components::Drawable,
     resources::{Camera, TileMap},
 };
+use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct FillTileMapToDrawSystem;
@@ -19,18 +20,33 @@ impl<'a> specs::System<'a> for FillTileMapToDrawSystem {
     )]
     fn run(&mut self, (camera_r, tile_map_r, drawable_c): Self::SystemData) {
         if let Some(mut tile_map_r) = tile_map_r {
-            tile_map_r.to_draw = vec![];
+            let mut rows = vec![];
+            let mut row_idxs = HashMap::new();
 
             // Draw entities in order of y first to emulate z-axis
+            // Entities taking up more than one tile are drawn once, on the lowest row they're in
             for y in camera_r.top..camera_r.bottom {
                 for x in camera_r.left..camera_r.right {
-                    if let Some(entity) = tile_map_r.get_tile(x, y).entity {
-                        if let Some(drawable) = drawable_c.get(entity) {
-                            tile_map_r.to_draw.push(drawable.clone());
+                    if let Some(entity) = tile_map_r.grid.entity(x, y) {
+                        if drawable_c.get(entity).is_some() {
+                            match row_idxs.get(&entity) {
+                                Some(&idx) => rows[idx] = (y, entity),
+                                None => {
+                                    row_idxs.insert(entity, rows.len());
+                                    rows.push((y, entity));
+                                }
+                            }
                         }
                     }
                 }
             }
+
+            rows.sort_by_key(|(y, _)| *y);
+
+            tile_map_r.to_draw = rows
+                .into_iter()
+                .filter_map(|(y, entity)| Some((y, drawable_c.get(entity)?.clone())))
+                .collect();
         }
     }
 }
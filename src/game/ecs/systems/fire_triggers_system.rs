@@ -0,0 +1,106 @@
+use super::{
+    components::{Player, Scripted, TargetPosition, Timer, Trigger},
+    resources::TriggerRequest,
+    save::SaveData,
+};
+use specs::Join;
+
+#[derive(Default, Debug)]
+pub struct FireTriggersSystem {
+    // Where the player's current step started, which is off the map when walking in from a
+    // connected one
+    stepping_from: Option<(isize, isize)>,
+}
+
+impl<'a> specs::System<'a> for FireTriggersSystem {
+    type SystemData = (
+        Option<specs::Write<'a, SaveData>>,
+        specs::Write<'a, TriggerRequest>,
+        specs::ReadStorage<'a, Player>,
+        specs::ReadStorage<'a, Scripted>,
+        specs::ReadStorage<'a, TargetPosition>,
+        specs::ReadStorage<'a, Timer>,
+        specs::ReadStorage<'a, Trigger>,
+    );
+
+    #[tracing::instrument(
+        skip(
+            save_data_r,
+            trigger_request_r,
+            player_c,
+            scripted_c,
+            target_position_c,
+            timer_c,
+            trigger_c
+        ),
+        name = "FireTriggersSystem"
+    )]
+    fn run(
+        &mut self,
+        (
+            save_data_r,
+            mut trigger_request_r,
+            player_c,
+            scripted_c,
+            target_position_c,
+            timer_c,
+            trigger_c,
+        ): Self::SystemData,
+    ) {
+        let mut save_data_r = match save_data_r {
+            Some(save_data_r) => save_data_r,
+            None => return,
+        };
+
+        for (_, scripted, target_position, timer) in
+            (&player_c, scripted_c.maybe(), &target_position_c, &timer_c).join()
+        {
+            // Help linter
+            #[cfg(debug_assertions)]
+            let target_position = target_position as &TargetPosition;
+            #[cfg(debug_assertions)]
+            let timer = timer as &Timer;
+
+            // Cutscenes moving the player around don't set anything off
+            if scripted.is_some() {
+                self.stepping_from = None;
+                continue;
+            }
+
+            if timer.should_tick() {
+                if self.stepping_from.is_none() {
+                    self.stepping_from = Some((target_position.from_x, target_position.from_y));
+                }
+
+                continue;
+            }
+
+            // Only finished steps count, so warps and loading into a map don't set anything off
+            let from = match self.stepping_from.take() {
+                Some(from) => from,
+                None => continue,
+            };
+            let to = (target_position.x as isize, target_position.y as isize);
+
+            let map = save_data_r.player.map.clone();
+
+            for trigger in (&trigger_c).join() {
+                // Help linter
+                #[cfg(debug_assertions)]
+                let trigger = trigger as &Trigger;
+
+                if !trigger.fires_on(from, to) || !trigger.can_fire(&save_data_r, &map) {
+                    continue;
+                }
+
+                if trigger.once {
+                    if let Some(map_definition) = save_data_r.maps.get_mut(&map) {
+                        map_definition.triggered.insert(trigger.id);
+                    }
+                }
+
+                trigger_request_r.requesting.push(trigger.function.clone());
+            }
+        }
+    }
+}
@@ -39,12 +39,14 @@ impl<'a> specs::System<'a> for FollowPlayerSystem {
                 let current_position = current_position as &CurrentPosition;
 
                 camera_r.x = nalgebra::clamp(
-                    current_position.x - config::VIEWPORT_TILES_WIDTH_F32 / 2. + 0.5,
+                    current_position.x + camera_r.pan_x - config::VIEWPORT_TILES_WIDTH_F32 / 2.
+                        + 0.5,
                     camera_bounds_r.min_x,
                     camera_bounds_r.max_x,
                 );
                 camera_r.y = nalgebra::clamp(
-                    current_position.y - config::VIEWPORT_TILES_HEIGHT_F32 / 2. + 0.5,
+                    current_position.y + camera_r.pan_y - config::VIEWPORT_TILES_HEIGHT_F32 / 2.
+                        + 0.5,
                     camera_bounds_r.min_y,
                     camera_bounds_r.max_y,
                 );
@@ -55,13 +57,13 @@ impl<'a> specs::System<'a> for FollowPlayerSystem {
                 #[cfg(debug_assertions)]
                 let current_position = current_position as &CurrentPosition;
 
-                camera_r.x = current_position.x;
-                camera_r.y = current_position.y;
+                camera_r.x = current_position.x + camera_r.pan_x;
+                camera_r.y = current_position.y + camera_r.pan_y;
             }
         }
 
         if let Some(tile_map_r) = tile_map_r {
-            let (max_x, max_y) = tile_map_r.dimensions();
+            let (max_x, max_y) = tile_map_r.grid.dimensions();
 
             camera_r.left = (camera_r.x as isize - 1).max(0) as usize;
             camera_r.right =
@@ -1,19 +1,29 @@
+mod advance_quests_system;
 mod animate_system;
 mod fill_tile_map_to_draw_system;
+mod fire_triggers_system;
 mod follow_player_system;
 mod move_background_draw_param_system;
 mod move_current_position_system;
+mod move_npc_target_position_system;
 mod move_player_target_position_system;
+mod open_gates_system;
+mod press_plates_system;
 mod update_background_tiles_system;
 mod update_draw_param_system;
 mod update_sprite_sheet_draw_param_system;
 
+pub use advance_quests_system::AdvanceQuestsSystem;
 pub use animate_system::AnimateSystem;
 pub use fill_tile_map_to_draw_system::FillTileMapToDrawSystem;
+pub use fire_triggers_system::FireTriggersSystem;
 pub use follow_player_system::FollowPlayerSystem;
 pub use move_background_draw_param_system::MoveBackgroundDrawParamSystem;
 pub use move_current_position_system::MoveCurrentPositionSystem;
+pub use move_npc_target_position_system::MoveNpcTargetPositionSystem;
 pub use move_player_target_position_system::MovePlayerTargetPositionSystem;
+pub use open_gates_system::OpenGatesSystem;
+pub use press_plates_system::PressPlatesSystem;
 pub use update_background_tiles_system::UpdateBackgroundTilesSystem;
 pub use update_draw_param_system::UpdateDrawParamSystem;
 pub use update_sprite_sheet_draw_param_system::UpdateSpriteSheetDrawParamSystem;
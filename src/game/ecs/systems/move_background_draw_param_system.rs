@@ -18,6 +18,17 @@ impl<'a> specs::System<'a> for MoveBackgroundDrawParamSystem {
                 dest.x = -camera_r.x * config::TILE_PIXELS_SIZE_F32;
                 dest.y = -camera_r.y * config::TILE_PIXELS_SIZE_F32;
             }
+
+            for connected_map in &mut tile_map_r.connected_maps {
+                if let ggez::graphics::Transform::Values { ref mut dest, .. } =
+                    connected_map.tile_map.spritesheet_param.trans
+                {
+                    dest.x =
+                        (connected_map.origin_x as f32 - camera_r.x) * config::TILE_PIXELS_SIZE_F32;
+                    dest.y =
+                        (connected_map.origin_y as f32 - camera_r.y) * config::TILE_PIXELS_SIZE_F32;
+                }
+            }
         }
     }
 }
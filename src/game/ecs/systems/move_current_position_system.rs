@@ -1,5 +1,5 @@
 use super::{
-    components::{CurrentPosition, TargetPosition, Timer},
+    components::{CurrentPosition, Drawable, TargetPosition, Timer},
     resources::DeltaTime,
 };
 use specs::Join;
@@ -12,21 +12,29 @@ impl<'a> specs::System<'a> for MoveCurrentPositionSystem {
         specs::WriteStorage<'a, CurrentPosition>,
         specs::WriteStorage<'a, TargetPosition>,
         specs::WriteStorage<'a, Timer>,
+        specs::WriteStorage<'a, Drawable>,
         specs::Read<'a, DeltaTime>,
     );
 
     #[tracing::instrument(
-        skip(current_position_c, target_position_c, timer_c, delta_r),
+        skip(current_position_c, target_position_c, timer_c, drawable_c, delta_r),
         name = "MoveCurrentPositionSystem"
     )]
     fn run(
         &mut self,
-        (mut current_position_c, mut target_position_c, mut timer_c, delta_r): Self::SystemData,
+        (
+            mut current_position_c,
+            mut target_position_c,
+            mut timer_c,
+            mut drawable_c,
+            delta_r,
+        ): Self::SystemData,
     ) {
-        for (current_position, target_position, timer) in (
+        for (current_position, target_position, timer, drawable) in (
             &mut current_position_c,
             &mut target_position_c,
             &mut timer_c,
+            (&mut drawable_c).maybe(),
         )
             .join()
         {
@@ -39,19 +47,29 @@ impl<'a> specs::System<'a> for MoveCurrentPositionSystem {
             let timer = timer as &mut Timer;
 
             if timer.should_tick() {
-                timer.tick(delta_r.secs);
+                // Hops cover two tiles, so they take as long as walking both of them
+                if target_position.is_hopping {
+                    timer.tick(delta_r.secs / 2.);
+                } else {
+                    timer.tick(delta_r.secs);
+                }
 
                 if timer.finished() {
                     current_position.x = target_position.x as f32;
                     current_position.y = target_position.y as f32;
 
-                    target_position.from_x = target_position.x;
-                    target_position.from_y = target_position.y;
+                    target_position.from_x = target_position.x as isize;
+                    target_position.from_y = target_position.y as isize;
+                    target_position.is_hopping = false;
 
                     timer.set_should_tick(false);
                 } else {
                     *current_position = target_position.get_current_position(timer);
                 }
+
+                if let Some(drawable) = drawable {
+                    drawable.offset_y = target_position.get_hop_height(timer);
+                }
             } else if target_position.is_moving {
                 target_position.is_moving = false;
             }
@@ -0,0 +1,306 @@
+use super::{
+    components::{
+        EntityName, FacingDirection, FacingPlayer, Footprint, NpcBehaviour, NpcMovement, Scripted,
+        SpriteSheet, TargetPosition, Timer,
+    },
+    config,
+    input::types::GameDirection,
+    maps::{self, PathOptions},
+    resources::{DeltaTime, Rng, ShouldUpdateBackgroundTiles, TileMap},
+    save::SaveData,
+};
+use specs::Join;
+
+const DIRECTIONS: [GameDirection; 4] = [
+    GameDirection::Up,
+    GameDirection::Down,
+    GameDirection::Left,
+    GameDirection::Right,
+];
+
+#[derive(Debug)]
+pub struct MoveNpcTargetPositionSystem;
+
+// NPCs never step onto ledges, so they can't end up somewhere they can't walk back from
+fn can_step_to(
+    tile_map: &TileMap,
+    entity: specs::Entity,
+    footprint: &Footprint,
+    x: isize,
+    y: isize,
+) -> bool {
+    tile_map.grid.has_room_for(entity, x, y, footprint)
+        && footprint
+            .positions(x as usize, y as usize)
+            .all(|(x, y)| tile_map.grid.ledge_direction(x, y).is_none())
+}
+
+// The direction to face next, and whether to step that way too
+fn next_move(
+    npc_movement: &mut NpcMovement,
+    tile_map: &TileMap,
+    entity: specs::Entity,
+    footprint: &Footprint,
+    position: (usize, usize),
+    rng: &mut Rng,
+) -> Option<(GameDirection, bool)> {
+    match &npc_movement.behaviour {
+        NpcBehaviour::Stand => None,
+        NpcBehaviour::LookAround => Some((DIRECTIONS[rng.below(DIRECTIONS.len())], false)),
+        NpcBehaviour::Wander { radius } => {
+            let direction = DIRECTIONS[rng.below(DIRECTIONS.len())];
+            let (direction_x, direction_y) = direction.to_xy();
+
+            let x = position.0 as isize + direction_x;
+            let y = position.1 as isize + direction_y;
+
+            let within_radius = (x - npc_movement.origin.0 as isize).abs() <= *radius as isize
+                && (y - npc_movement.origin.1 as isize).abs() <= *radius as isize;
+
+            Some((
+                direction,
+                within_radius && can_step_to(tile_map, entity, footprint, x, y),
+            ))
+        }
+        NpcBehaviour::Patrol { route } => {
+            if route.is_empty() {
+                return None;
+            }
+
+            // Pause at each point before heading to the next one
+            if position == route[npc_movement.next_waypoint % route.len()] {
+                npc_movement.next_waypoint = (npc_movement.next_waypoint + 1) % route.len();
+                return None;
+            }
+
+            let waypoint = route[npc_movement.next_waypoint % route.len()];
+
+            // Other entities in the way are walked around, or waited on if there's no way around
+            let path_options = PathOptions {
+                mover: Some((entity, footprint)),
+                allow_ledges: false,
+                ..PathOptions::default()
+            };
+            let direction =
+                *maps::find_path(&tile_map.grid, position, waypoint, &path_options)?.first()?;
+            let (direction_x, direction_y) = direction.to_xy();
+
+            let can_step = can_step_to(
+                tile_map,
+                entity,
+                footprint,
+                position.0 as isize + direction_x,
+                position.1 as isize + direction_y,
+            );
+
+            Some((direction, can_step))
+        }
+    }
+}
+
+impl<'a> specs::System<'a> for MoveNpcTargetPositionSystem {
+    type SystemData = (
+        Option<specs::Write<'a, TileMap>>,
+        specs::Write<'a, ShouldUpdateBackgroundTiles>,
+        specs::Write<'a, Rng>,
+        specs::Read<'a, DeltaTime>,
+        Option<specs::Write<'a, SaveData>>,
+        specs::Entities<'a>,
+        specs::ReadStorage<'a, EntityName>,
+        specs::ReadStorage<'a, Footprint>,
+        specs::ReadStorage<'a, FacingPlayer>,
+        specs::ReadStorage<'a, Scripted>,
+        specs::WriteStorage<'a, NpcMovement>,
+        specs::WriteStorage<'a, TargetPosition>,
+        specs::WriteStorage<'a, Timer>,
+        specs::WriteStorage<'a, SpriteSheet>,
+        specs::WriteStorage<'a, FacingDirection>,
+    );
+
+    #[tracing::instrument(
+        skip(
+            tile_map_r,
+            should_update_background_tiles_r,
+            rng_r,
+            delta_time_r,
+            save_data_r,
+            entities,
+            entity_name_c,
+            footprint_c,
+            facing_player_c,
+            scripted_c,
+            npc_movement_c,
+            target_position_c,
+            timer_c,
+            sprite_sheet_c,
+            facing_direction_c
+        ),
+        name = "MoveNpcTargetPositionSystem"
+    )]
+    fn run(
+        &mut self,
+        (
+            tile_map_r,
+            mut should_update_background_tiles_r,
+            mut rng_r,
+            delta_time_r,
+            mut save_data_r,
+            entities,
+            entity_name_c,
+            footprint_c,
+            facing_player_c,
+            scripted_c,
+            mut npc_movement_c,
+            mut target_position_c,
+            mut timer_c,
+            mut sprite_sheet_c,
+            mut facing_direction_c,
+        ): Self::SystemData,
+    ) {
+        let mut tile_map = match tile_map_r {
+            Some(tile_map) => tile_map,
+            None => return,
+        };
+
+        for (
+            entity,
+            entity_name,
+            footprint,
+            _,
+            _,
+            npc_movement,
+            target_position,
+            timer,
+            sprite_sheet,
+            facing_direction,
+        ) in (
+            &entities,
+            (&entity_name_c).maybe(),
+            (&footprint_c).maybe(),
+            // Held still while talking to the player
+            !&facing_player_c,
+            !&scripted_c,
+            &mut npc_movement_c,
+            &mut target_position_c,
+            &mut timer_c,
+            &mut sprite_sheet_c,
+            &mut facing_direction_c,
+        )
+            .join()
+        {
+            // Help linter
+            #[cfg(debug_assertions)]
+            let npc_movement = npc_movement as &mut NpcMovement;
+            #[cfg(debug_assertions)]
+            let target_position = target_position as &mut TargetPosition;
+            #[cfg(debug_assertions)]
+            let timer = timer as &mut Timer;
+            #[cfg(debug_assertions)]
+            let sprite_sheet = sprite_sheet as &mut SpriteSheet;
+            #[cfg(debug_assertions)]
+            let facing_direction = facing_direction as &mut FacingDirection;
+
+            let footprint = footprint.cloned().unwrap_or_default();
+
+            // Still stepping to the last tile
+            if timer.should_tick() {
+                continue;
+            }
+
+            sprite_sheet.set_row(match facing_direction.direction {
+                GameDirection::Down => config::ENTITY_SPRITE_SHEET_IDX_IDLE_DOWN,
+                GameDirection::Left => config::ENTITY_SPRITE_SHEET_IDX_IDLE_LEFT,
+                GameDirection::Up => config::ENTITY_SPRITE_SHEET_IDX_IDLE_UP,
+                GameDirection::Right => config::ENTITY_SPRITE_SHEET_IDX_IDLE_RIGHT,
+            });
+
+            npc_movement.wait.tick(delta_time_r.secs);
+            if !npc_movement.wait.finished() {
+                continue;
+            }
+
+            let position = (target_position.x, target_position.y);
+
+            let (direction, should_step) = match next_move(
+                npc_movement,
+                &tile_map,
+                entity,
+                &footprint,
+                position,
+                &mut rng_r,
+            ) {
+                Some(next_move) => next_move,
+                None => {
+                    npc_movement.wait.set_duration(
+                        rng_r.range_f32(config::NPC_WAIT_SECONDS_MIN, config::NPC_WAIT_SECONDS_MAX),
+                    );
+                    npc_movement.wait.reset();
+                    continue;
+                }
+            };
+
+            facing_direction.direction = direction;
+
+            // Patrols keep walking until they reach a point, everything else waits between moves
+            let wait_seconds = match npc_movement.behaviour {
+                NpcBehaviour::Patrol { .. } if should_step => 0.,
+                _ => rng_r.range_f32(config::NPC_WAIT_SECONDS_MIN, config::NPC_WAIT_SECONDS_MAX),
+            };
+            npc_movement.wait.set_duration(wait_seconds);
+            npc_movement.wait.reset();
+
+            if !should_step {
+                sprite_sheet.set_row(match direction {
+                    GameDirection::Down => config::ENTITY_SPRITE_SHEET_IDX_IDLE_DOWN,
+                    GameDirection::Left => config::ENTITY_SPRITE_SHEET_IDX_IDLE_LEFT,
+                    GameDirection::Up => config::ENTITY_SPRITE_SHEET_IDX_IDLE_UP,
+                    GameDirection::Right => config::ENTITY_SPRITE_SHEET_IDX_IDLE_RIGHT,
+                });
+                continue;
+            }
+
+            let (direction_x, direction_y) = direction.to_xy();
+            let target_position_x = (position.0 as isize + direction_x) as usize;
+            let target_position_y = (position.1 as isize + direction_y) as usize;
+
+            // Taken as soon as the step starts, so nothing else can step onto the same tiles
+            tile_map.grid.move_entity(
+                entity,
+                position,
+                (target_position_x, target_position_y),
+                &footprint,
+            );
+
+            timer.reset();
+            timer.set_should_tick(true);
+
+            should_update_background_tiles_r.0 = true;
+
+            target_position.x = target_position_x;
+            target_position.y = target_position_y;
+            target_position.is_moving = true;
+
+            sprite_sheet.set_row(match direction {
+                GameDirection::Down => config::ENTITY_SPRITE_SHEET_IDX_WALK_DOWN,
+                GameDirection::Right => config::ENTITY_SPRITE_SHEET_IDX_WALK_RIGHT,
+                GameDirection::Up => config::ENTITY_SPRITE_SHEET_IDX_WALK_UP,
+                GameDirection::Left => config::ENTITY_SPRITE_SHEET_IDX_WALK_LEFT,
+            });
+
+            // Saved NPCs come back wherever they were left
+            if let (Some(save_data), Some(entity_name)) = (&mut save_data_r, entity_name) {
+                let map = save_data.player.map.clone();
+
+                if let Some(entity_instance) = save_data
+                    .maps
+                    .get_mut(&map)
+                    .and_then(|map_definition| map_definition.entity_instances.get_mut(entity_name))
+                {
+                    entity_instance.position.x = target_position_x;
+                    entity_instance.position.y = target_position_y;
+                    entity_instance.position.facing = Some(direction);
+                }
+            }
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use super::{
     components::{
-        CurrentPosition, Door, FacingDirection, Player, SpriteSheet, TargetPosition, Timer,
+        CurrentPosition, Door, FacingDirection, Footprint, Player, Pushable, SpriteSheet,
+        TargetPosition, Timer,
     },
     config,
     input::types::GameDirection,
-    maps::TileType,
-    resources::{DoorRequest, PlayerMovementRequest, ShouldUpdateBackgroundTiles, TileMap},
+    resources::{
+        ConnectionRequest, DoorRequest, MapConnectionStep, MovementMode, PlayerMovementRequest,
+        ShouldUpdateBackgroundTiles, TileMap,
+    },
     save::SaveData,
 };
 use specs::Join;
@@ -26,6 +29,8 @@ type SystemData<'a> = (
     Option<specs::Write<'a, SaveData>>,
     specs::Write<'a, DoorRequest>,
     specs::ReadStorage<'a, Door>,
+    specs::Write<'a, ConnectionRequest>,
+    specs::ReadStorage<'a, Pushable>,
 );
 
 fn move_target_position<'a>(
@@ -37,53 +42,98 @@ fn move_target_position<'a>(
     timer: &mut Timer,
     sprite_sheet: &mut SpriteSheet,
     direction: &GameDirection,
+    movement_mode: MovementMode,
     door_request: &mut DoorRequest,
     door_c: specs::ReadStorage<'a, Door>,
-) {
+    connection_request: &mut ConnectionRequest,
+    pushable_c: &specs::ReadStorage<'a, Pushable>,
+) -> Option<specs::Entity> {
     let (direction_x, direction_y) = direction.to_xy();
 
-    let tile_map_dimensions = tile_map.dimensions();
+    let tile_map_dimensions = tile_map.grid.dimensions();
 
     let rounded_current_position_x = current_position.x.round() as usize;
     let rounded_current_position_y = current_position.y.round() as usize;
 
-    let target_position_x = nalgebra::clamp(
-        rounded_current_position_x as isize + direction_x,
-        0,
-        tile_map_dimensions.0 as isize - 1,
-    ) as usize;
+    let target_position_x = rounded_current_position_x as isize + direction_x;
+    let target_position_y = rounded_current_position_y as isize + direction_y;
+
+    // Walking off the edge of the map goes into whichever map is connected there, if any
+    if target_position_x < 0
+        || target_position_y < 0
+        || target_position_x >= tile_map_dimensions.0 as isize
+        || target_position_y >= tile_map_dimensions.1 as isize
+    {
+        if let Some((connected_map, (x, y))) =
+            tile_map.connected_map_at(target_position_x, target_position_y)
+        {
+            if !connected_map.tile_map.grid.blocks_movement(x, y) {
+                connection_request.requesting = Some(MapConnectionStep {
+                    to_map: connected_map.map_name.clone(),
+                    x,
+                    y,
+                    direction: *direction,
+                });
+            }
+        }
+
+        return None;
+    }
+
+    let mut target_position_x = target_position_x as usize;
+    let mut target_position_y = target_position_y as usize;
+    let mut is_hopping = false;
+
+    // Ledges can only be hopped down in their direction, landing on the tile past them
+    if let Some(ledge_direction) = tile_map
+        .grid
+        .ledge_direction(target_position_x, target_position_y)
+    {
+        let landing_position_x = target_position_x as isize + direction_x;
+        let landing_position_y = target_position_y as isize + direction_y;
 
-    let target_position_y = nalgebra::clamp(
-        rounded_current_position_y as isize + direction_y,
-        0,
-        tile_map_dimensions.1 as isize - 1,
-    ) as usize;
+        if ledge_direction != *direction
+            || landing_position_x < 0
+            || landing_position_y < 0
+            || landing_position_x >= tile_map_dimensions.0 as isize
+            || landing_position_y >= tile_map_dimensions.1 as isize
+        {
+            return None;
+        }
 
-    let target_tile = tile_map.get_tile(target_position_x, target_position_y);
+        target_position_x = landing_position_x as usize;
+        target_position_y = landing_position_y as usize;
+        is_hopping = true;
+    }
 
     // Another entity is already in the target location
-    if let Some(entity) = target_tile.entity {
+    if let Some(entity) = tile_map.grid.entity(target_position_x, target_position_y) {
         if let Some(door) = door_c.get(entity) {
             door_request.requesting = Some((*door).clone());
         }
 
-        return;
+        // Walking into something pushable pushes it instead, unless it's landed on from a ledge
+        if !is_hopping && pushable_c.get(entity).is_some() {
+            return Some(entity);
+        }
+
+        return None;
     }
 
     // Can't walk on tile
-    if let Some(tile_type) = &target_tile.tile_type {
-        match tile_type {
-            TileType::Wall | TileType::Water => return,
-        }
+    if tile_map
+        .grid
+        .blocks_movement(target_position_x, target_position_y)
+    {
+        return None;
     }
 
     if target_position_x != rounded_current_position_x
         || target_position_y != rounded_current_position_y
     {
         let player_entity = tile_map
-            .get_tile_mut(rounded_current_position_x, rounded_current_position_y)
-            .entity
-            .take()
+            .grid
+            .set_entity(rounded_current_position_x, rounded_current_position_y, None)
             .expect(&format!(
                 "Player entity isn't in tile_map @ [{}, {}]\n{:#?}\n{:#?}",
                 rounded_current_position_x,
@@ -93,10 +143,10 @@ fn move_target_position<'a>(
             ));
 
         tile_map
-            .get_tile_mut(target_position_x, target_position_y)
-            .entity
-            .replace(player_entity);
+            .grid
+            .set_entity(target_position_x, target_position_y, Some(player_entity));
 
+        timer.set_duration(movement_mode.seconds_per_tile());
         timer.reset();
         timer.set_should_tick(true);
 
@@ -105,23 +155,86 @@ fn move_target_position<'a>(
         target_position.x = target_position_x;
         target_position.y = target_position_y;
         target_position.is_moving = true;
+        target_position.is_hopping = is_hopping;
 
         save_data.player.position.x = target_position_x;
         save_data.player.position.y = target_position_y;
 
-        sprite_sheet.set_row(match direction {
-            GameDirection::Down => config::ENTITY_SPRITE_SHEET_IDX_WALK_DOWN,
-            GameDirection::Right => config::ENTITY_SPRITE_SHEET_IDX_WALK_RIGHT,
-            GameDirection::Up => config::ENTITY_SPRITE_SHEET_IDX_WALK_UP,
-            GameDirection::Left => config::ENTITY_SPRITE_SHEET_IDX_WALK_LEFT,
-        });
+        sprite_sheet.set_row(movement_mode.sprite_sheet_row(direction));
+    }
+
+    None
+}
+
+// Pushing moves at the same speed as the player, who waits behind until it stops. Pushable
+// entities don't go onto ledges, so they can't end up somewhere they can't be pushed back from
+fn push<'a>(
+    should_update_background_tiles_r: &mut ShouldUpdateBackgroundTiles,
+    save_data: &mut SaveData,
+    tile_map: &mut TileMap,
+    entity: specs::Entity,
+    direction: &GameDirection,
+    movement_mode: MovementMode,
+    pushable_c: &specs::ReadStorage<'a, Pushable>,
+    target_position_c: &mut specs::WriteStorage<'a, TargetPosition>,
+    timer_c: &mut specs::WriteStorage<'a, Timer>,
+) {
+    let (pushable, target_position, timer) = match (
+        pushable_c.get(entity),
+        target_position_c.get_mut(entity),
+        timer_c.get_mut(entity),
+    ) {
+        (Some(pushable), Some(target_position), Some(timer)) => (pushable, target_position, timer),
+        _ => return,
+    };
+
+    if timer.should_tick() {
+        return;
+    }
+
+    let (direction_x, direction_y) = direction.to_xy();
+
+    let from = (target_position.x, target_position.y);
+    let to_x = from.0 as isize + direction_x;
+    let to_y = from.1 as isize + direction_y;
+
+    if !tile_map
+        .grid
+        .has_room_for(entity, to_x, to_y, &Footprint::default())
+        || tile_map
+            .grid
+            .ledge_direction(to_x as usize, to_y as usize)
+            .is_some()
+    {
+        return;
+    }
+
+    let to = (to_x as usize, to_y as usize);
+
+    tile_map
+        .grid
+        .move_entity(entity, from, to, &Footprint::default());
+
+    timer.set_duration(movement_mode.seconds_per_tile());
+    timer.reset();
+    timer.set_should_tick(true);
+
+    should_update_background_tiles_r.0 = true;
+
+    target_position.x = to.0;
+    target_position.y = to.1;
+    target_position.is_moving = true;
+
+    let map = save_data.player.map.clone();
+    if let Some(map_definition) = save_data.maps.get_mut(&map) {
+        map_definition.pushed.insert(pushable.id, to);
     }
 }
 
 fn handle_input<'a>(
     (
         mut tile_map_r,
-        _player_movement_request_r,
+        player_movement_request_r,
         mut should_update_background_tiles_r,
         player_c,
         current_position_c,
@@ -129,13 +242,17 @@ fn handle_input<'a>(
         mut timer_c,
         mut sprite_sheet_c,
         mut facing_direction_c,
-        opt_save_data_r,
+        mut opt_save_data_r,
         mut door_request_r,
         door_c,
+        mut connection_request_r,
+        pushable_c,
     ): SystemData<'a>,
     direction: &GameDirection,
 ) {
     if let Some(tile_map) = &mut tile_map_r {
+        let mut pushing = None;
+
         for (_, current_position, target_position, timer, sprite_sheet, facing_direction) in (
             &player_c,
             &current_position_c,
@@ -158,23 +275,27 @@ fn handle_input<'a>(
 
             if timer.finished() {
                 if target_position.is_moving || facing_direction.direction == *direction {
-                    let mut save_data =
-                        opt_save_data_r.expect("SaveData resource not in game world");
+                    let save_data = opt_save_data_r
+                        .as_mut()
+                        .expect("SaveData resource not in game world");
 
                     facing_direction.direction = *direction;
                     save_data.player.position.facing = Some(*direction);
 
-                    move_target_position(
+                    pushing = move_target_position(
                         &mut should_update_background_tiles_r,
-                        &mut save_data,
+                        save_data,
                         tile_map,
                         current_position,
                         target_position,
                         timer,
                         sprite_sheet,
                         direction,
+                        player_movement_request_r.movement_mode,
                         &mut door_request_r,
                         door_c,
+                        &mut connection_request_r,
+                        &pushable_c,
                     );
                 }
             } else if !target_position.is_moving {
@@ -186,7 +307,23 @@ fn handle_input<'a>(
                 });
             }
 
-            return;
+            break;
+        }
+
+        if let Some(entity) = pushing {
+            if let Some(save_data) = &mut opt_save_data_r {
+                push(
+                    &mut should_update_background_tiles_r,
+                    save_data,
+                    tile_map,
+                    entity,
+                    direction,
+                    player_movement_request_r.movement_mode,
+                    &pushable_c,
+                    &mut target_position_c,
+                    &mut timer_c,
+                );
+            }
         }
     }
 }
@@ -208,6 +345,8 @@ impl<'a> specs::System<'a> for MovePlayerTargetPositionSystem {
             opt_save_data_r,
             door_request_r,
             door_c,
+            connection_request_r,
+            pushable_c,
         ),
         name = "MovePlayerTargetPositionSystem"
     )]
@@ -226,6 +365,8 @@ impl<'a> specs::System<'a> for MovePlayerTargetPositionSystem {
             opt_save_data_r,
             door_request_r,
             door_c,
+            connection_request_r,
+            pushable_c,
         ): Self::SystemData,
     ) {
         // Last requested direction
@@ -244,6 +385,8 @@ impl<'a> specs::System<'a> for MovePlayerTargetPositionSystem {
                     opt_save_data_r,
                     door_request_r,
                     door_c,
+                    connection_request_r,
+                    pushable_c,
                 ),
                 &direction,
             );
@@ -264,6 +407,8 @@ impl<'a> specs::System<'a> for MovePlayerTargetPositionSystem {
                     opt_save_data_r,
                     door_request_r,
                     door_c,
+                    connection_request_r,
+                    pushable_c,
                 ),
                 &direction,
             );
@@ -284,6 +429,8 @@ impl<'a> specs::System<'a> for MovePlayerTargetPositionSystem {
                     opt_save_data_r,
                     door_request_r,
                     door_c,
+                    connection_request_r,
+                    pushable_c,
                 ),
                 &direction,
             );
@@ -0,0 +1,58 @@
+use super::{
+    components::{Footprint, Gate},
+    resources::TileMap,
+    save::SaveData,
+};
+use specs::Join;
+
+#[derive(Debug)]
+pub struct OpenGatesSystem;
+
+impl<'a> specs::System<'a> for OpenGatesSystem {
+    type SystemData = (
+        Option<specs::Write<'a, TileMap>>,
+        Option<specs::Read<'a, SaveData>>,
+        specs::Entities<'a>,
+        specs::ReadStorage<'a, Gate>,
+        specs::ReadStorage<'a, Footprint>,
+    );
+
+    #[tracing::instrument(
+        skip(tile_map_r, save_data_r, entities, gate_c, footprint_c),
+        name = "OpenGatesSystem"
+    )]
+    fn run(&mut self, (tile_map_r, save_data_r, entities, gate_c, footprint_c): Self::SystemData) {
+        let (mut tile_map_r, save_data_r) = match (tile_map_r, save_data_r) {
+            (Some(tile_map_r), Some(save_data_r)) => (tile_map_r, save_data_r),
+            _ => return,
+        };
+
+        let map = &save_data_r.player.map;
+
+        for (entity, gate, footprint) in (&entities, &gate_c, &footprint_c).join() {
+            // Help linter
+            #[cfg(debug_assertions)]
+            let gate = gate as &Gate;
+            #[cfg(debug_assertions)]
+            let footprint = footprint as &Footprint;
+
+            if gate.is_open(&save_data_r, map) {
+                for (x, y) in footprint.positions(gate.x, gate.y) {
+                    if tile_map_r.grid.entity(x, y) == Some(entity) {
+                        tile_map_r.grid.set_entity(x, y, None);
+                    }
+                }
+            } else if tile_map_r.grid.has_room_for(
+                entity,
+                gate.x as isize,
+                gate.y as isize,
+                footprint,
+            ) {
+                // Closing waits until nothing is standing in the way
+                for (x, y) in footprint.positions(gate.x, gate.y) {
+                    tile_map_r.grid.set_entity(x, y, Some(entity));
+                }
+            }
+        }
+    }
+}
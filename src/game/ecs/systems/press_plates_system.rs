@@ -0,0 +1,66 @@
+use super::{
+    components::{PressurePlate, Pushable, TargetPosition, Timer},
+    save::SaveData,
+};
+use specs::Join;
+use std::collections::HashSet;
+
+#[derive(Debug)]
+pub struct PressPlatesSystem;
+
+impl<'a> specs::System<'a> for PressPlatesSystem {
+    type SystemData = (
+        Option<specs::Write<'a, SaveData>>,
+        specs::ReadStorage<'a, Pushable>,
+        specs::ReadStorage<'a, TargetPosition>,
+        specs::ReadStorage<'a, Timer>,
+        specs::ReadStorage<'a, PressurePlate>,
+    );
+
+    #[tracing::instrument(
+        skip(save_data_r, pushable_c, target_position_c, timer_c, pressure_plate_c),
+        name = "PressPlatesSystem"
+    )]
+    fn run(
+        &mut self,
+        (save_data_r, pushable_c, target_position_c, timer_c, pressure_plate_c): Self::SystemData,
+    ) {
+        let mut save_data_r = match save_data_r {
+            Some(save_data_r) => save_data_r,
+            None => return,
+        };
+
+        // Anything still being pushed isn't resting anywhere yet
+        let resting: HashSet<(usize, usize)> = (&pushable_c, &target_position_c, &timer_c)
+            .join()
+            .filter(|(_, _, timer)| !timer.should_tick())
+            .map(|(_, target_position, _)| (target_position.x, target_position.y))
+            .collect();
+
+        // Plates can share a state, which stays set while any of them are pressed
+        let pressed: HashSet<_> = (&pressure_plate_c)
+            .join()
+            .filter(|pressure_plate| resting.contains(&(pressure_plate.x, pressure_plate.y)))
+            .map(|pressure_plate| pressure_plate.state.clone())
+            .collect();
+
+        let map = save_data_r.player.map.clone();
+
+        let map_definition = match save_data_r.maps.get_mut(&map) {
+            Some(map_definition) => map_definition,
+            None => return,
+        };
+
+        for pressure_plate in (&pressure_plate_c).join() {
+            // Help linter
+            #[cfg(debug_assertions)]
+            let pressure_plate = pressure_plate as &PressurePlate;
+
+            if pressed.contains(&pressure_plate.state) {
+                map_definition.states.insert(pressure_plate.state.clone());
+            } else {
+                map_definition.states.remove(&pressure_plate.state);
+            }
+        }
+    }
+}
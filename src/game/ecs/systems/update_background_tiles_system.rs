@@ -6,6 +6,48 @@ use super::{
 #[derive(Debug)]
 pub struct UpdateBackgroundTilesSystem;
 
+// Fills the map's layers with the tiles inside of the area, which is in the map's own tiles
+fn update_layers(tile_map: &mut TileMap, left: usize, top: usize, right: usize, bottom: usize) {
+    let TileMap {
+        grid,
+        layers,
+        uvs,
+        animation,
+        ..
+    } = tile_map;
+
+    for layer in layers.iter_mut() {
+        layer.clear(top, bottom);
+    }
+
+    // The map binary's checksum was checked when it loaded, so its chunks can be read
+    grid.load_around(left, top, right, bottom)
+        .expect("Couldn't load map chunks");
+
+    // Update background tiles to draw
+    for (layer_idx, layer) in layers.iter_mut().enumerate() {
+        for y in top..bottom {
+            for x in left..right {
+                if let Some(tile_idx) = grid.tile_id(layer_idx, x, y) {
+                    let tile_idx = animation
+                        .get(&tile_idx)
+                        .map(|frame| frame.tile_id())
+                        .unwrap_or(tile_idx);
+
+                    layer.spritesheet_mut(y).add(
+                        ggez::graphics::DrawParam::default()
+                            .src(uvs[tile_idx])
+                            .dest([
+                                x as f32 * config::TILE_PIXELS_SIZE_F32,
+                                y as f32 * config::TILE_PIXELS_SIZE_F32,
+                            ]),
+                    );
+                }
+            }
+        }
+    }
+}
+
 impl<'a> specs::System<'a> for UpdateBackgroundTilesSystem {
     type SystemData = (
         specs::Write<'a, ShouldUpdateBackgroundTiles>,
@@ -25,66 +67,30 @@ impl<'a> specs::System<'a> for UpdateBackgroundTilesSystem {
             should_update_background_tiles_r.0 = false;
 
             if let Some(mut tile_map) = tile_map_r {
-                // Update background tiles to draw
-                let (max_x, _) = tile_map.dimensions();
-
-                tile_map.background.clear();
-                tile_map.overlay.clear();
-
-                let background_width = tile_map.background_width;
-                let background_height = tile_map.background_height;
-                let inverse_background_width = 1. / background_width as f32;
-                let inverse_background_height = 1. / background_height as f32;
+                update_layers(
+                    &mut tile_map,
+                    camera_r.left,
+                    camera_r.top,
+                    camera_r.right,
+                    camera_r.bottom,
+                );
 
-                let overlay_width = tile_map.overlay_width;
-                let overlay_height = tile_map.overlay_height;
-                let inverse_overlay_width = 1. / overlay_width as f32;
-                let inverse_overlay_height = 1. / overlay_height as f32;
+                // Connected maps fill in whatever the camera can see past the edges
+                for connected_map in &mut tile_map.connected_maps {
+                    let (width, height) = connected_map.tile_map.grid.dimensions();
 
-                for y in camera_r.top..camera_r.bottom {
-                    for x in camera_r.left..camera_r.right {
-                        if let Some(background_idx) = tile_map.background_indices[max_x * y + x] {
-                            tile_map.background.add(
-                                ggez::graphics::DrawParam::default()
-                                    .src(
-                                        [
-                                            (background_idx % background_width) as f32
-                                                * inverse_background_width,
-                                            (background_idx / background_width) as f32
-                                                * inverse_background_height,
-                                            inverse_background_width,
-                                            inverse_background_height,
-                                        ]
-                                        .into(),
-                                    )
-                                    .dest([
-                                        x as f32 * config::TILE_PIXELS_SIZE_F32,
-                                        y as f32 * config::TILE_PIXELS_SIZE_F32,
-                                    ]),
-                            );
-                        }
+                    let left = camera_r.x as isize - 1 - connected_map.origin_x;
+                    let top = camera_r.y as isize - 1 - connected_map.origin_y;
+                    let right = left + config::VIEWPORT_TILES_WIDTH_USIZE as isize + 2;
+                    let bottom = top + config::VIEWPORT_TILES_HEIGHT_USIZE as isize + 2;
 
-                        if let Some(overlay_idx) = tile_map.overlay_indices[max_x * y + x] {
-                            tile_map.overlay.add(
-                                ggez::graphics::DrawParam::default()
-                                    .src(
-                                        [
-                                            (overlay_idx % overlay_width) as f32
-                                                * inverse_overlay_width,
-                                            (overlay_idx / overlay_width) as f32
-                                                * inverse_overlay_height,
-                                            inverse_overlay_width,
-                                            inverse_overlay_height,
-                                        ]
-                                        .into(),
-                                    )
-                                    .dest([
-                                        x as f32 * config::TILE_PIXELS_SIZE_F32,
-                                        y as f32 * config::TILE_PIXELS_SIZE_F32,
-                                    ]),
-                            );
-                        }
-                    }
+                    update_layers(
+                        &mut connected_map.tile_map,
+                        left.max(0) as usize,
+                        top.max(0) as usize,
+                        (right.max(0) as usize).min(width),
+                        (bottom.max(0) as usize).min(height),
+                    );
                 }
             }
         }
@@ -27,7 +27,7 @@ impl<'a> specs::System<'a> for UpdateDrawParamSystem {
 
             drawable.draw_params = drawable.draw_params.dest([
                 (current_position.x - camera_r.x) * config::TILE_PIXELS_SIZE_F32,
-                (current_position.y - camera_r.y) * config::TILE_PIXELS_SIZE_F32,
+                (current_position.y - camera_r.y - drawable.offset_y) * config::TILE_PIXELS_SIZE_F32,
             ]);
         }
     }
@@ -1,4 +1,5 @@
 use super::{
+    ecs::resources::{ItemRegistry, MapRegistry, QuestRegistry},
     error::types::GameResult,
     events,
     input::types::GameInput,
@@ -13,7 +14,10 @@ use super::{
 use specs::WorldExt;
 use std::{cell::RefCell, rc::Rc};
 
-#[derive(Default)]
+#[cfg(all(debug_assertions, feature = "hot-reload"))]
+use super::hot_reload::{self, AssetWatcher};
+
+#[derive(Default, Clone)]
 pub struct InputState {
     pub gamepad_axis_x: f32,
     pub gamepad_axis_y: f32,
@@ -41,8 +45,17 @@ impl std::fmt::Debug for GameState {
 
 impl GameState {
     fn new(ctx: &mut ggez::Context, settings: Settings) -> GameResult<Self> {
+        let mut world = world::create_world();
+        let map_registry = MapRegistry::load(ctx)?;
+        let item_registry = ItemRegistry::load(ctx)?;
+        let quest_registry = QuestRegistry::load(ctx, &map_registry, &item_registry)?;
+
+        world.insert(map_registry);
+        world.insert(item_registry);
+        world.insert(quest_registry);
+
         Ok(Self {
-            world: world::create_world(),
+            world,
             input_state: InputState::default(),
             render_state: RenderState::new(ctx, &settings)?,
             settings,
@@ -55,6 +68,8 @@ pub struct GlobalState {
     pub scene_manager: SceneManager,
     pub game_state: GameState,
     pub delta_secs: f32,
+    #[cfg(all(debug_assertions, feature = "hot-reload"))]
+    asset_watcher: Option<AssetWatcher>,
 }
 
 impl GlobalState {
@@ -70,10 +85,22 @@ impl GlobalState {
         let mut scene_manager = SceneManager::default();
         scene_manager.switch(&mut game_state, ctx, SceneSwitch::ReplaceAll(scene_builder))?;
 
+        // Hot reloading is only a convenience, so the game still runs without it
+        #[cfg(all(debug_assertions, feature = "hot-reload"))]
+        let asset_watcher = match AssetWatcher::new(ctx) {
+            Ok(asset_watcher) => Some(asset_watcher),
+            Err(e) => {
+                log::warn!("Hot reloading is off: {}", e);
+                None
+            }
+        };
+
         Ok(Self {
             scene_manager,
             game_state,
             delta_secs: 0.,
+            #[cfg(all(debug_assertions, feature = "hot-reload"))]
+            asset_watcher,
         })
     }
 
@@ -99,6 +126,22 @@ impl GlobalState {
 impl events::EventHandler for GlobalState {
     #[tracing::instrument(name = "GlobalState::update")]
     fn update(&mut self, ctx: &mut ggez::Context) -> GameResult {
+        #[cfg(all(debug_assertions, feature = "hot-reload"))]
+        if let Some(asset_watcher) = &self.asset_watcher {
+            hot_reload::apply_changes(&mut self.game_state, ctx, asset_watcher.poll());
+        }
+
+        if let Some(scene) =
+            self.scene_manager
+                .update_transition(&mut self.game_state, ctx, self.delta_secs)?
+        {
+            scene.borrow_mut().dispose(&mut self.game_state, ctx)?;
+        }
+
+        if self.scene_manager.is_switching() {
+            return Ok(());
+        }
+
         let mut scene_switch = None;
 
         for scene in self.scene_manager.update_stack() {
@@ -131,7 +174,7 @@ impl events::EventHandler for GlobalState {
             scene.borrow().draw(&self.game_state, ctx)?;
         }
 
-        Ok(())
+        self.scene_manager.draw_transition(ctx)
     }
 
     fn resize_event(&mut self, ctx: &mut ggez::Context, width: f32, height: f32) -> GameResult {
@@ -178,6 +221,11 @@ impl events::EventHandler for GlobalState {
         } else if let Some(game_input) =
             GameInput::from_keycode(&keycode, true, &self.game_state.settings)
         {
+            // Input is locked during transitions, but letting go still counts so nothing stays held
+            if self.scene_manager.is_transitioning() && !game_input.is_release() {
+                return Ok(());
+            }
+
             let mut scene_switch = None;
 
             for scene in self.scene_manager.input_stack() {
@@ -212,6 +260,10 @@ impl events::EventHandler for GlobalState {
         if let Some(game_input) =
             GameInput::from_keycode(&keycode, false, &self.game_state.settings)
         {
+            if self.scene_manager.is_transitioning() && !game_input.is_release() {
+                return Ok(());
+            }
+
             let mut scene_switch = None;
 
             for scene in self.scene_manager.input_stack() {
@@ -247,6 +299,10 @@ impl events::EventHandler for GlobalState {
         if let Some(game_input) =
             GameInput::from_gamepad_button(&btn, true, &self.game_state.settings)
         {
+            if self.scene_manager.is_transitioning() && !game_input.is_release() {
+                return Ok(());
+            }
+
             let mut scene_switch = None;
 
             for scene in self.scene_manager.input_stack() {
@@ -282,6 +338,10 @@ impl events::EventHandler for GlobalState {
         if let Some(game_input) =
             GameInput::from_gamepad_button(&btn, false, &self.game_state.settings)
         {
+            if self.scene_manager.is_transitioning() && !game_input.is_release() {
+                return Ok(());
+            }
+
             let mut scene_switch = None;
 
             for scene in self.scene_manager.input_stack() {
@@ -350,6 +410,10 @@ impl events::EventHandler for GlobalState {
         let game_input =
             GameInput::from_gamepad_axes(gamepad_axis_x, gamepad_axis_y, controller_stick_deadzone);
 
+        if self.scene_manager.is_transitioning() && !game_input.is_release() {
+            return Ok(());
+        }
+
         let mut scene_switch = None;
 
         for scene in self.scene_manager.input_stack() {
@@ -0,0 +1,307 @@
+// Debug builds watch the map sources and the assets, and swap whatever changed into the running game
+#[path = "../../../build/map_compiler/mod.rs"]
+mod map_compiler;
+
+use super::{
+    config,
+    ecs::{
+        components::{Drawable, ImageFile},
+        resources::{
+            ItemRegistry, MapRegistry, QuestRegistry, ShouldUpdateBackgroundTiles, TileMap,
+        },
+    },
+    error::types::GameResult,
+    game_state::GameState,
+    maps::{self, TileMapDefinition},
+    save::SaveData,
+    scripting::ScriptEngine,
+};
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use specs::Join;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    time::Duration,
+};
+
+// Map sources only exist next to the code, so they're found from where the game was built
+const TILED_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/build_assets/tiled");
+const TILED_MAPS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/build_assets/tiled/maps");
+
+const BINARY_MAPS_PATH: &str = "bin/maps";
+
+// Editors tend to write a file a few times when saving, so wait for them to settle
+const WATCH_DELAY: Duration = Duration::from_millis(250);
+
+#[derive(Default, Debug)]
+pub struct AssetChanges {
+    pub maps: bool,
+    pub items: bool,
+    pub quests: bool,
+    pub scripts: bool,
+    pub images: Vec<String>,
+}
+
+pub struct AssetWatcher {
+    // Events stop once the watcher is dropped
+    _watcher: RecommendedWatcher,
+    events: Receiver<DebouncedEvent>,
+    tiled_path: PathBuf,
+    assets_path: PathBuf,
+}
+
+impl std::fmt::Debug for AssetWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AssetWatcher { ... }")
+    }
+}
+
+impl AssetWatcher {
+    pub fn new(ctx: &ggez::Context) -> GameResult<Self> {
+        let tiled_path = PathBuf::from(TILED_PATH).canonicalize()?;
+        let assets_path = ctx.filesystem.assets_path.canonicalize()?;
+
+        let (sender, events) = mpsc::channel();
+
+        let mut watcher = notify::watcher(sender, WATCH_DELAY).map_err(watch_error)?;
+        watcher
+            .watch(&tiled_path, RecursiveMode::Recursive)
+            .map_err(watch_error)?;
+        watcher
+            .watch(&assets_path, RecursiveMode::Recursive)
+            .map_err(watch_error)?;
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            tiled_path,
+            assets_path,
+        })
+    }
+
+    pub fn poll(&self) -> AssetChanges {
+        let mut changes = AssetChanges::default();
+
+        for event in self.events.try_iter() {
+            let path = match event {
+                DebouncedEvent::Create(path)
+                | DebouncedEvent::Write(path)
+                | DebouncedEvent::Rename(_, path) => path,
+                _ => continue,
+            };
+
+            if path.starts_with(&self.tiled_path) {
+                changes.maps = true;
+            } else if let Ok(relative_path) = path.strip_prefix(&self.assets_path) {
+                // The map registry is read again along with the maps
+                if relative_path
+                    == Path::new(config::MAP_REGISTRY_FILE_PATH.trim_start_matches('/'))
+                {
+                    changes.maps = true;
+                    continue;
+                }
+
+                if relative_path
+                    == Path::new(config::ITEM_REGISTRY_FILE_PATH.trim_start_matches('/'))
+                {
+                    changes.items = true;
+                    continue;
+                }
+
+                if relative_path
+                    == Path::new(config::QUEST_REGISTRY_FILE_PATH.trim_start_matches('/'))
+                {
+                    changes.quests = true;
+                    continue;
+                }
+
+                if relative_path.extension().map_or(false, |ext| ext == "rhai") {
+                    changes.scripts = true;
+                    continue;
+                }
+
+                // Compiled maps are written by the reload itself
+                if relative_path.starts_with(BINARY_MAPS_PATH)
+                    || relative_path.extension().map_or(true, |ext| ext != "png")
+                {
+                    continue;
+                }
+
+                // Tilesets point at images in assets/ too, which get packed into map atlases
+                changes.maps = true;
+
+                let filename = format!("/{}", relative_path.to_string_lossy().replace('\\', "/"));
+
+                if !changes.images.contains(&filename) {
+                    changes.images.push(filename);
+                }
+            }
+        }
+
+        changes
+    }
+}
+
+fn watch_error(e: notify::Error) -> ggez::GameError {
+    ggez::GameError::CustomError(format!("Couldn't watch assets for changes: {}", e))
+}
+
+pub fn apply_changes(game_state: &mut GameState, ctx: &mut ggez::Context, changes: AssetChanges) {
+    if changes.maps {
+        match reload_maps(game_state, ctx) {
+            Ok(()) => log::info!("Reloaded maps"),
+            Err(e) => log::error!("Couldn't reload maps, keeping the current map: {}", e),
+        }
+    }
+
+    // Items are only looked up when they're needed, so the old registry can just be swapped out
+    if changes.items {
+        match ItemRegistry::load(ctx) {
+            Ok(item_registry) => {
+                game_state.world.insert(item_registry);
+                log::info!("Reloaded items");
+            }
+            Err(e) => log::error!("Couldn't reload items, keeping the current ones: {}", e),
+        }
+    }
+
+    // The journal in the save only holds statuses, so it carries over to the new quests
+    if changes.quests {
+        let quest_registry = QuestRegistry::load(
+            ctx,
+            &game_state.world.fetch::<MapRegistry>(),
+            &game_state.world.fetch::<ItemRegistry>(),
+        );
+
+        match quest_registry {
+            Ok(quest_registry) => {
+                game_state.world.insert(quest_registry);
+                log::info!("Reloaded quests");
+            }
+            Err(e) => log::error!("Couldn't reload quests, keeping the current ones: {}", e),
+        }
+    }
+
+    // Scripts are only read when they're run, so they just need to be forgotten
+    if changes.scripts {
+        if let Some(script_engine) = game_state.world.get_mut::<ScriptEngine>() {
+            script_engine.clear();
+        }
+
+        log::info!("Reloaded scripts");
+    }
+
+    for filename in &changes.images {
+        match reload_image(game_state, ctx, filename) {
+            Ok(()) => log::info!("Reloaded {}", filename),
+            Err(e) => log::error!("Couldn't reload {}: {}", filename, e),
+        }
+    }
+}
+
+fn reload_maps(game_state: &mut GameState, ctx: &mut ggez::Context) -> GameResult {
+    let mut diagnostics = vec![];
+
+    map_compiler::compile_maps(
+        &PathBuf::from(TILED_MAPS_PATH),
+        &ctx.filesystem.assets_path.join(BINARY_MAPS_PATH),
+        &mut diagnostics,
+    )
+    .map_err(|e| ggez::GameError::CustomError(format!("Couldn't compile maps: {}", e)))?;
+
+    if !diagnostics.is_empty() {
+        let messages: Vec<String> = diagnostics.iter().map(ToString::to_string).collect();
+
+        return Err(ggez::GameError::CustomError(format!(
+            "{} error(s)\n{}",
+            messages.len(),
+            messages.join("\n")
+        )));
+    }
+
+    let map_registry = MapRegistry::load(ctx)?;
+
+    // Not in a map right now, so there's nothing to swap out
+    if !game_state.world.has_value::<TileMap>() {
+        game_state.world.insert(map_registry);
+        return Ok(());
+    }
+
+    let map_name = game_state.world.fetch::<SaveData>().player.map.clone();
+
+    // Everything that can fail happens before the current map is touched
+    let tile_map_definition = TileMapDefinition::load(ctx, &map_registry, &map_name)?;
+    let connected_maps = maps::load_connected_maps(ctx, &map_registry, &tile_map_definition)?;
+    let camera_bounds = tile_map_definition.camera_bounds(&connected_maps);
+
+    let (mut entities, spritesheet_param) = {
+        let tile_map = game_state.world.fetch::<TileMap>();
+
+        let entities: HashMap<(usize, usize), specs::Entity> = tile_map
+            .grid
+            .entities()
+            .map(|(position, entity)| (*position, *entity))
+            .collect();
+
+        (entities, tile_map.spritesheet_param)
+    };
+
+    let mut tile_map = tile_map_definition.to_tile_map(ctx, &mut entities)?;
+    tile_map.spritesheet_param = spritesheet_param;
+    tile_map.connected_maps = connected_maps;
+
+    for ((x, y), entity) in entities {
+        log::warn!(
+            "{:?} at ({}, {}) isn't on the reloaded {} map anymore",
+            entity,
+            x,
+            y,
+            map_name
+        );
+    }
+
+    game_state.world.insert(map_registry);
+    game_state.world.insert(camera_bounds);
+    game_state.world.insert(tile_map);
+    game_state
+        .world
+        .fetch_mut::<ShouldUpdateBackgroundTiles>()
+        .0 = true;
+
+    Ok(())
+}
+
+fn reload_image(game_state: &mut GameState, ctx: &mut ggez::Context, filename: &str) -> GameResult {
+    // Nothing with an image file has been created yet
+    if !game_state
+        .world
+        .has_value::<specs::storage::MaskedStorage<ImageFile>>()
+    {
+        return Ok(());
+    }
+
+    let image = Arc::new(ggez::graphics::Image::new(ctx, filename)?);
+
+    let (image_file_c, mut drawable_c): (
+        specs::ReadStorage<ImageFile>,
+        specs::WriteStorage<Drawable>,
+    ) = game_state.world.system_data();
+
+    for (image_file, drawable) in (&image_file_c, &mut drawable_c).join() {
+        // Help linter
+        #[cfg(debug_assertions)]
+        let image_file = image_file as &ImageFile;
+        #[cfg(debug_assertions)]
+        let drawable = drawable as &mut Drawable;
+
+        if image_file.filename == filename {
+            drawable.drawable = image.clone();
+        }
+    }
+
+    Ok(())
+}
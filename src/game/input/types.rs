@@ -30,6 +30,15 @@ impl GameDirection {
             Self::Right => Self::RIGHT,
         }
     }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
@@ -40,6 +49,7 @@ pub enum GameButton {
     Right,
     Primary,
     Secondary,
+    Run,
     Start,
     Select, // TODO: Should we use X/Y buttons? Or L/R buttons?
 }
@@ -51,6 +61,13 @@ pub enum GameInput {
 }
 
 impl GameInput {
+    pub fn is_release(&self) -> bool {
+        match self {
+            Self::Direction { direction } => direction.is_none(),
+            Self::Button { pressed, .. } => !pressed,
+        }
+    }
+
     pub fn from_keycode(keycode: &KeyCode, pressed: bool, settings: &Settings) -> Option<Self> {
         settings
             .game_settings
@@ -0,0 +1,196 @@
+use super::super::error::types::GameResult;
+use std::collections::HashMap;
+
+// Must match CHUNK_SIZE in build/map_compiler
+pub const CHUNK_SIZE: usize = 16;
+
+// How many chunks past the edges of the camera stay loaded, so walking back and forth over a
+// chunk border doesn't keep decoding the same chunks, and NPCs just out of view keep moving
+const RESIDENT_MARGIN: usize = 1;
+
+// One list of tile ids per layer, which is empty when the layer has nothing in the chunk
+pub type ChunkLayers = Vec<Vec<Option<usize>>>;
+
+// Every chunk of the map, still encoded the way they were in the map binary
+#[derive(Debug)]
+pub struct ChunkSource {
+    index: HashMap<(usize, usize), (usize, usize)>,
+    data: Vec<u8>,
+}
+
+impl ChunkSource {
+    pub fn new(index: Vec<(usize, usize, usize, usize)>, data: Vec<u8>) -> Self {
+        Self {
+            index: index
+                .into_iter()
+                .map(|(x, y, offset, length)| ((x, y), (offset, length)))
+                .collect(),
+            data,
+        }
+    }
+
+    // Older maps stored whole layers, so split them up the same way the map compiler does
+    pub fn from_layers(width: usize, height: usize, layers: &[Vec<Option<usize>>]) -> Self {
+        let mut index = vec![];
+        let mut data = vec![];
+
+        for chunk_y in 0..height.div_ceil(CHUNK_SIZE) {
+            for chunk_x in 0..width.div_ceil(CHUNK_SIZE) {
+                let chunk: ChunkLayers = layers
+                    .iter()
+                    .map(|tile_ids| {
+                        let chunk_tile_ids: Vec<Option<usize>> = (0..CHUNK_SIZE * CHUNK_SIZE)
+                            .map(|idx| {
+                                let x = chunk_x * CHUNK_SIZE + idx % CHUNK_SIZE;
+                                let y = chunk_y * CHUNK_SIZE + idx / CHUNK_SIZE;
+
+                                if x < width && y < height {
+                                    tile_ids[y * width + x]
+                                } else {
+                                    None
+                                }
+                            })
+                            .collect();
+
+                        if chunk_tile_ids.iter().all(Option::is_none) {
+                            vec![]
+                        } else {
+                            chunk_tile_ids
+                        }
+                    })
+                    .collect();
+
+                if chunk.iter().all(Vec::is_empty) {
+                    continue;
+                }
+
+                let bytes = bincode::serialize(&chunk).expect("Tile ids can always be serialized");
+
+                index.push((chunk_x, chunk_y, data.len(), bytes.len()));
+                data.extend_from_slice(&bytes);
+            }
+        }
+
+        Self::new(index, data)
+    }
+
+    pub fn read(&self, chunk_x: usize, chunk_y: usize) -> GameResult<Option<ChunkLayers>> {
+        let (offset, length) = match self.index.get(&(chunk_x, chunk_y)) {
+            Some(entry) => *entry,
+            None => return Ok(None),
+        };
+
+        let bytes = offset
+            .checked_add(length)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or_else(|| {
+                ggez::GameError::CustomError(format!(
+                    "Chunk ({}, {}) is out of bounds",
+                    chunk_x, chunk_y
+                ))
+            })?;
+
+        bincode::deserialize(bytes).map(Some).map_err(|e| {
+            ggez::GameError::CustomError(format!(
+                "Couldn't read chunk ({}, {}): {}",
+                chunk_x, chunk_y, e
+            ))
+        })
+    }
+}
+
+// Only the chunks around the camera are decoded, everything else stays encoded until the camera
+// comes near it and is dropped again once the camera moves away
+#[derive(Debug)]
+pub struct ChunkCache {
+    source: ChunkSource,
+    // In chunks, so chunks past the edges of the map are never loaded
+    width: usize,
+    height: usize,
+    // Chunks without any tiles in the map binary are still loaded, as chunks with no layers
+    resident: HashMap<(usize, usize), ChunkLayers>,
+}
+
+impl ChunkCache {
+    pub fn new(source: ChunkSource, map_width: usize, map_height: usize) -> Self {
+        Self {
+            source,
+            width: map_width.div_ceil(CHUNK_SIZE),
+            height: map_height.div_ceil(CHUNK_SIZE),
+            resident: HashMap::new(),
+        }
+    }
+
+    // Loads every chunk touching the area in tiles, and drops the ones that are far away from it
+    pub fn load_around(
+        &mut self,
+        left: usize,
+        top: usize,
+        right: usize,
+        bottom: usize,
+    ) -> GameResult {
+        if left >= right || top >= bottom {
+            self.resident.clear();
+            return Ok(());
+        }
+
+        let min_x = (left / CHUNK_SIZE).saturating_sub(RESIDENT_MARGIN);
+        let min_y = (top / CHUNK_SIZE).saturating_sub(RESIDENT_MARGIN);
+        let max_x = ((right - 1) / CHUNK_SIZE + RESIDENT_MARGIN).min(self.width.saturating_sub(1));
+        let max_y =
+            ((bottom - 1) / CHUNK_SIZE + RESIDENT_MARGIN).min(self.height.saturating_sub(1));
+
+        self.resident
+            .retain(|(x, y), _| min_x <= *x && *x <= max_x && min_y <= *y && *y <= max_y);
+
+        for chunk_y in min_y..=max_y {
+            for chunk_x in min_x..=max_x {
+                if self.resident.contains_key(&(chunk_x, chunk_y)) {
+                    continue;
+                }
+
+                let chunk = self.source.read(chunk_x, chunk_y)?.unwrap_or_default();
+                self.resident.insert((chunk_x, chunk_y), chunk);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub fn load_all(&mut self) -> GameResult {
+        self.load_around(0, 0, self.width * CHUNK_SIZE, self.height * CHUNK_SIZE)
+    }
+
+    pub fn is_resident(&self, x: usize, y: usize) -> bool {
+        self.resident
+            .contains_key(&(x / CHUNK_SIZE, y / CHUNK_SIZE))
+    }
+
+    // Tiles in chunks that aren't loaded are treated as empty
+    pub fn tile_id(&self, layer: usize, x: usize, y: usize) -> Option<usize> {
+        let chunk = self.resident.get(&(x / CHUNK_SIZE, y / CHUNK_SIZE))?;
+
+        chunk.get(layer)?.get(chunk_idx(x, y)).copied().flatten()
+    }
+
+    // The tile on each layer that has one, from the bottom layer up
+    pub fn tile_ids(&self, x: usize, y: usize) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        let idx = chunk_idx(x, y);
+
+        self.resident
+            .get(&(x / CHUNK_SIZE, y / CHUNK_SIZE))
+            .into_iter()
+            .flat_map(move |chunk| {
+                chunk
+                    .iter()
+                    .filter_map(move |tile_ids| tile_ids.get(idx).copied().flatten())
+            })
+    }
+}
+
+// Where a tile is in each layer's tile ids. Layers with nothing in the chunk have no tile ids at
+// all, so they never find one
+fn chunk_idx(x: usize, y: usize) -> usize {
+    (y % CHUNK_SIZE) * CHUNK_SIZE + x % CHUNK_SIZE
+}
@@ -0,0 +1,134 @@
+use super::super::{
+    ecs::{
+        components::{CurrentPosition, MapName, Player, SpriteSheet, TargetPosition, Timer},
+        resources::{ConnectedMap, MapRegistry, PlayerMovementRequest},
+        systems::{
+            FillTileMapToDrawSystem, FollowPlayerSystem, MoveBackgroundDrawParamSystem,
+            UpdateBackgroundTilesSystem, UpdateDrawParamSystem,
+        },
+    },
+    error::types::GameResult,
+    game_state::GameState,
+    input::types::GameDirection,
+};
+use super::TileMapDefinition;
+use serde::{Deserialize, Serialize};
+use specs::{Join, RunNow};
+use std::collections::HashMap;
+
+// The connected map is lined up against this map's edge in the direction, shifted along that
+// edge by offset tiles
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MapConnection {
+    pub direction: GameDirection,
+    pub map: MapName,
+    pub offset: isize,
+}
+
+pub fn load_connected_maps(
+    ctx: &mut ggez::Context,
+    map_registry: &MapRegistry,
+    tile_map_definition: &TileMapDefinition,
+) -> GameResult<Vec<ConnectedMap>> {
+    tile_map_definition
+        .connections
+        .iter()
+        .map(|connection| {
+            let connected_map_definition =
+                TileMapDefinition::load(ctx, map_registry, &connection.map)?;
+
+            let (origin_x, origin_y) = match connection.direction {
+                GameDirection::Up => (
+                    connection.offset,
+                    -(connected_map_definition.height as isize),
+                ),
+                GameDirection::Down => (connection.offset, tile_map_definition.height as isize),
+                GameDirection::Left => (
+                    -(connected_map_definition.width as isize),
+                    connection.offset,
+                ),
+                GameDirection::Right => (tile_map_definition.width as isize, connection.offset),
+            };
+
+            // Entities in connected maps aren't spawned until the player walks into them
+            let tile_map = connected_map_definition.to_tile_map(ctx, &mut HashMap::new())?;
+
+            Ok(ConnectedMap {
+                map_name: connection.map.clone(),
+                origin_x,
+                origin_y,
+                tile_map,
+            })
+        })
+        .collect()
+}
+
+// Finishes the step the player started on the edge of the map they came from, so walking
+// between connected maps doesn't stop to load
+pub fn step_into_map(game_state: &mut GameState, direction: GameDirection) -> GameResult {
+    {
+        let movement_mode = game_state
+            .world
+            .fetch::<PlayerMovementRequest>()
+            .movement_mode;
+
+        let (
+            player_c,
+            mut current_position_c,
+            mut target_position_c,
+            mut timer_c,
+            mut sprite_sheet_c,
+        ): (
+            specs::ReadStorage<Player>,
+            specs::WriteStorage<CurrentPosition>,
+            specs::WriteStorage<TargetPosition>,
+            specs::WriteStorage<Timer>,
+            specs::WriteStorage<SpriteSheet>,
+        ) = game_state.world.system_data();
+
+        for (_, current_position, target_position, timer, sprite_sheet) in (
+            &player_c,
+            &mut current_position_c,
+            &mut target_position_c,
+            &mut timer_c,
+            &mut sprite_sheet_c,
+        )
+            .join()
+        {
+            // Help linter
+            #[cfg(debug_assertions)]
+            let current_position = current_position as &mut CurrentPosition;
+            #[cfg(debug_assertions)]
+            let target_position = target_position as &mut TargetPosition;
+            #[cfg(debug_assertions)]
+            let timer = timer as &mut Timer;
+            #[cfg(debug_assertions)]
+            let sprite_sheet = sprite_sheet as &mut SpriteSheet;
+
+            let (direction_x, direction_y) = direction.to_xy();
+
+            // The tile the step started from is just past the edge of this map
+            target_position.from_x = target_position.x as isize - direction_x;
+            target_position.from_y = target_position.y as isize - direction_y;
+            target_position.is_moving = true;
+
+            current_position.x = target_position.from_x as f32;
+            current_position.y = target_position.from_y as f32;
+
+            timer.set_duration(movement_mode.seconds_per_tile());
+            timer.reset();
+            timer.set_should_tick(true);
+
+            sprite_sheet.set_row(movement_mode.sprite_sheet_row(&direction));
+        }
+    }
+
+    // The new map would otherwise be drawn empty for a frame before the dispatcher gets to it
+    FollowPlayerSystem.run_now(&game_state.world);
+    MoveBackgroundDrawParamSystem.run_now(&game_state.world);
+    UpdateDrawParamSystem.run_now(&game_state.world);
+    FillTileMapToDrawSystem.run_now(&game_state.world);
+    UpdateBackgroundTilesSystem.run_now(&game_state.world);
+
+    Ok(())
+}
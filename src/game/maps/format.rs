@@ -0,0 +1,589 @@
+// Reads the map container written by build/map_compiler/format.rs
+use super::super::{
+    ecs::components::{EntityName, ItemId, MapName, StateCondition, StateName, TriggerEvent},
+    error::types::GameResult,
+    input::types::GameDirection,
+};
+use super::{
+    Atlas, ChunkSource, DrawOrder, MapConnection, MapObject, MapObjectKind, MapTile,
+    MapTileAnimationFrame, TileLayer, TileMapDefinition, TileProperty,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, convert::TryInto};
+
+const MAGIC: [u8; 8] = *b"RGPMAP\0\0";
+const VERSION: u32 = 11;
+
+// Maps before version 3 pointed at tilesets which were stitched together on the GPU when
+// loading, so they can't be upgraded without a graphics context
+const MIN_VERSION: u32 = 3;
+
+const SECTION_INFO: [u8; 4] = *b"INFO";
+const SECTION_ATLAS: [u8; 4] = *b"ATLS";
+const SECTION_TILES: [u8; 4] = *b"TILE";
+const SECTION_LAYERS: [u8; 4] = *b"LAYR";
+const SECTION_CHUNK_INDEX: [u8; 4] = *b"CHNK";
+const SECTION_CHUNK_DATA: [u8; 4] = *b"CDAT";
+const SECTION_OBJECTS: [u8; 4] = *b"OBJS";
+const SECTION_CONNECTIONS: [u8; 4] = *b"CONN";
+
+const HEADER_LEN: usize = 8 + 4 + 8 + 4;
+const SECTION_ENTRY_LEN: usize = 4 + 8 + 8;
+
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn load_error(filename: &str, message: String) -> ggez::GameError {
+    ggez::GameError::CustomError(format!("Couldn't load map {}: {}", filename, message))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+struct Sections<'a> {
+    filename: &'a str,
+    entries: Vec<([u8; 4], &'a [u8])>,
+}
+
+impl<'a> Sections<'a> {
+    fn bytes(&self, tag: [u8; 4]) -> GameResult<&'a [u8]> {
+        self.entries
+            .iter()
+            .find(|(entry_tag, _)| *entry_tag == tag)
+            .map(|(_, data)| *data)
+            .ok_or_else(|| {
+                load_error(
+                    self.filename,
+                    format!("Missing section {}", String::from_utf8_lossy(&tag)),
+                )
+            })
+    }
+
+    fn read<T: serde::de::DeserializeOwned>(&self, tag: [u8; 4]) -> GameResult<T> {
+        let tag_name = String::from_utf8_lossy(&tag).to_string();
+
+        bincode::deserialize(self.bytes(tag)?).map_err(|e| {
+            load_error(
+                self.filename,
+                format!("Couldn't read section {}: {}", tag_name, e),
+            )
+        })
+    }
+}
+
+pub fn read_tile_map_definition(filename: &str, bytes: &[u8]) -> GameResult<TileMapDefinition> {
+    if bytes.len() < HEADER_LEN || bytes[0..8] != MAGIC {
+        return Err(load_error(
+            filename,
+            "Not a map binary, or one from before maps were versioned. Try rebuilding the maps"
+                .to_string(),
+        ));
+    }
+
+    let version = read_u32(bytes, 8);
+    let hash = read_u64(bytes, 12);
+    let section_count = read_u32(bytes, 20) as usize;
+
+    if version < MIN_VERSION || version > VERSION {
+        return Err(load_error(
+            filename,
+            format!(
+                "Map format version {} isn't supported, this build reads versions {} to {}. Try rebuilding the maps",
+                version, MIN_VERSION, VERSION
+            ),
+        ));
+    }
+
+    let body = &bytes[HEADER_LEN..];
+    if fnv1a_hash(body) != hash {
+        return Err(load_error(
+            filename,
+            "Checksum doesn't match, the file is corrupt. Try rebuilding the maps".to_string(),
+        ));
+    }
+
+    let table_len = section_count * SECTION_ENTRY_LEN;
+    if body.len() < table_len {
+        return Err(load_error(
+            filename,
+            "Section table is truncated".to_string(),
+        ));
+    }
+
+    let mut entries = vec![];
+    for idx in 0..section_count {
+        let at = HEADER_LEN + idx * SECTION_ENTRY_LEN;
+
+        let tag: [u8; 4] = bytes[at..at + 4].try_into().unwrap();
+        let offset = read_u64(bytes, at + 4) as usize;
+        let length = read_u64(bytes, at + 12) as usize;
+
+        let data = offset
+            .checked_add(length)
+            .and_then(|end| bytes.get(offset..end))
+            .ok_or_else(|| {
+                load_error(
+                    filename,
+                    format!("Section {} is out of bounds", String::from_utf8_lossy(&tag)),
+                )
+            })?;
+
+        entries.push((tag, data));
+    }
+
+    let sections = Sections { filename, entries };
+
+    // Older versions get upgraded here as the format changes
+    match version {
+        3 => read_v3(&sections),
+        4 => read_v4(&sections),
+        5 => read_v5(&sections),
+        6 => read_v6(&sections),
+        // Versions 8 to 10 added new kinds of objects, which older maps just don't have
+        7..=10 => read_v10(&sections),
+        11 => read_v11(&sections),
+        _ => unreachable!(),
+    }
+}
+
+fn read_v3(sections: &Sections) -> GameResult<TileMapDefinition> {
+    let tiles: Vec<v3::MapTile> = sections.read(SECTION_TILES)?;
+    let tiles = tiles.into_iter().map(v3::MapTile::upgrade).collect();
+
+    read_unchunked_map(sections, tiles)
+}
+
+fn read_v4(sections: &Sections) -> GameResult<TileMapDefinition> {
+    let tiles: Vec<MapTile> = sections.read(SECTION_TILES)?;
+
+    read_unchunked_map(sections, tiles)
+}
+
+fn read_v5(sections: &Sections) -> GameResult<TileMapDefinition> {
+    read_chunked_map(sections, v6::read_objects(sections)?, vec![])
+}
+
+fn read_v6(sections: &Sections) -> GameResult<TileMapDefinition> {
+    let connections: Vec<v6::MapConnection> = sections.read(SECTION_CONNECTIONS)?;
+    let connections = connections
+        .into_iter()
+        .map(v6::MapConnection::upgrade)
+        .collect();
+
+    read_chunked_map(sections, v6::read_objects(sections)?, connections)
+}
+
+fn read_v10(sections: &Sections) -> GameResult<TileMapDefinition> {
+    let connections: Vec<MapConnection> = sections.read(SECTION_CONNECTIONS)?;
+
+    read_chunked_map(sections, v10::read_objects(sections)?, connections)
+}
+
+fn read_v11(sections: &Sections) -> GameResult<TileMapDefinition> {
+    let objects: Vec<MapObject> = sections.read(SECTION_OBJECTS)?;
+    let connections: Vec<MapConnection> = sections.read(SECTION_CONNECTIONS)?;
+
+    read_chunked_map(sections, objects, connections)
+}
+
+fn read_chunked_map(
+    sections: &Sections,
+    objects: Vec<MapObject>,
+    connections: Vec<MapConnection>,
+) -> GameResult<TileMapDefinition> {
+    let tiles: Vec<MapTile> = sections.read(SECTION_TILES)?;
+    let layers: Vec<TileLayer> = sections.read(SECTION_LAYERS)?;
+    let chunk_index: Vec<(usize, usize, usize, usize)> = sections.read(SECTION_CHUNK_INDEX)?;
+    let chunk_data = sections.bytes(SECTION_CHUNK_DATA)?.to_vec();
+
+    read_map(
+        sections,
+        tiles,
+        layers,
+        ChunkSource::new(chunk_index, chunk_data),
+        objects,
+        connections,
+    )
+}
+
+// Before version 5 layers held every tile id in the map instead of being split into chunks
+fn read_unchunked_map(sections: &Sections, tiles: Vec<MapTile>) -> GameResult<TileMapDefinition> {
+    let (width, height): (usize, usize) = sections.read(SECTION_INFO)?;
+    let layers: Vec<v4::TileLayer> = sections.read(SECTION_LAYERS)?;
+
+    let (layers, layer_tile_ids): (Vec<TileLayer>, Vec<Vec<Option<usize>>>) = layers
+        .into_iter()
+        .map(|layer| {
+            (
+                TileLayer {
+                    name: layer.name,
+                    draw_order: layer.draw_order,
+                },
+                layer.tile_ids,
+            )
+        })
+        .unzip();
+
+    let chunks = ChunkSource::from_layers(width, height, &layer_tile_ids);
+
+    // Connections were added in version 6
+    read_map(
+        sections,
+        tiles,
+        layers,
+        chunks,
+        v6::read_objects(sections)?,
+        vec![],
+    )
+}
+
+// The map size and atlas have been the same since version 3
+fn read_map(
+    sections: &Sections,
+    tiles: Vec<MapTile>,
+    layers: Vec<TileLayer>,
+    chunks: ChunkSource,
+    objects: Vec<MapObject>,
+    connections: Vec<MapConnection>,
+) -> GameResult<TileMapDefinition> {
+    let (width, height): (usize, usize) = sections.read(SECTION_INFO)?;
+    let atlas: Atlas = sections.read(SECTION_ATLAS)?;
+
+    Ok(TileMapDefinition {
+        width,
+        height,
+        atlas,
+        tiles,
+        layers,
+        chunks,
+        objects,
+        connections,
+    })
+}
+
+// Version 3 animations had no frame durations, every frame was shown for half a second
+mod v3 {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct MapTile {
+        pub id: usize,
+        pub properties: HashMap<String, TileProperty>,
+        pub animation: Option<Vec<MapTileAnimationFrame>>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct MapTileAnimationFrame {
+        pub tile_id: usize,
+    }
+
+    impl MapTile {
+        pub fn upgrade(self) -> super::MapTile {
+            super::MapTile {
+                id: self.id,
+                properties: self.properties,
+                animation: self.animation.map(|frames| {
+                    frames
+                        .into_iter()
+                        .map(|frame| super::MapTileAnimationFrame {
+                            tile_id: frame.tile_id,
+                            duration_millis: 500,
+                        })
+                        .collect()
+                }),
+            }
+        }
+    }
+}
+
+// Version 4 layers weren't split into chunks
+mod v4 {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct TileLayer {
+        pub name: String,
+        pub draw_order: DrawOrder,
+        pub tile_ids: Vec<Option<usize>>,
+    }
+}
+
+// Before version 7 map names were an enum, so doors and connections stored the variant index
+mod v6 {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub enum MapName {
+        PalletTown,
+        Varrock,
+    }
+
+    impl MapName {
+        fn upgrade(self) -> super::MapName {
+            match self {
+                Self::PalletTown => super::MapName::new("PalletTown"),
+                Self::Varrock => super::MapName::new("Varrock"),
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct MapObject {
+        pub id: usize,
+        pub name: String,
+        pub x: usize,
+        pub y: usize,
+        pub width: usize,
+        pub height: usize,
+        pub kind: MapObjectKind,
+    }
+
+    #[derive(Deserialize)]
+    pub enum MapObjectKind {
+        Door {
+            door_id: usize,
+            to_map: MapName,
+            to_id: usize,
+            facing: GameDirection,
+        },
+        Sign {
+            text: String,
+        },
+        Npc {
+            entity_name: super::v10::EntityName,
+            facing: Option<GameDirection>,
+        },
+    }
+
+    #[derive(Deserialize)]
+    pub struct MapConnection {
+        pub direction: GameDirection,
+        pub map: MapName,
+        pub offset: isize,
+    }
+
+    impl MapObject {
+        fn upgrade(self) -> super::MapObject {
+            super::MapObject {
+                id: self.id,
+                name: self.name,
+                x: self.x,
+                y: self.y,
+                width: self.width,
+                height: self.height,
+                kind: match self.kind {
+                    MapObjectKind::Door {
+                        door_id,
+                        to_map,
+                        to_id,
+                        facing,
+                    } => super::MapObjectKind::Door {
+                        door_id,
+                        to_map: to_map.upgrade(),
+                        to_id,
+                        facing,
+                    },
+                    MapObjectKind::Sign { text } => super::MapObjectKind::Sign { text },
+                    MapObjectKind::Npc {
+                        entity_name,
+                        facing,
+                    } => super::MapObjectKind::Npc {
+                        entity_name: entity_name.upgrade(),
+                        facing,
+                    },
+                },
+            }
+        }
+    }
+
+    impl MapConnection {
+        pub fn upgrade(self) -> super::MapConnection {
+            super::MapConnection {
+                direction: self.direction,
+                map: self.map.upgrade(),
+                offset: self.offset,
+            }
+        }
+    }
+
+    pub fn read_objects(sections: &Sections) -> GameResult<Vec<super::MapObject>> {
+        let objects: Vec<MapObject> = sections.read(SECTION_OBJECTS)?;
+
+        Ok(objects.into_iter().map(MapObject::upgrade).collect())
+    }
+}
+
+// Before version 11 entity and state names were enums too, so objects stored the variant index
+mod v10 {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub enum EntityName {
+        WiseOldMan,
+    }
+
+    impl EntityName {
+        pub fn upgrade(self) -> super::EntityName {
+            match self {
+                Self::WiseOldMan => super::EntityName::new("WiseOldMan"),
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub enum StateName {
+        TestState,
+        MetWiseOldMan,
+        VisitedPalletTown,
+        PalletTownPlatePressed,
+    }
+
+    impl StateName {
+        fn upgrade(self) -> super::StateName {
+            match self {
+                Self::TestState => super::StateName::new("TestState"),
+                Self::MetWiseOldMan => super::StateName::new("MetWiseOldMan"),
+                Self::VisitedPalletTown => super::StateName::new("VisitedPalletTown"),
+                Self::PalletTownPlatePressed => super::StateName::new("PalletTownPlatePressed"),
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct StateCondition {
+        pub state: StateName,
+        pub in_map: bool,
+        pub is_set: bool,
+    }
+
+    impl StateCondition {
+        fn upgrade(self) -> super::StateCondition {
+            super::StateCondition {
+                state: self.state.upgrade(),
+                in_map: self.in_map,
+                is_set: self.is_set,
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct MapObject {
+        pub id: usize,
+        pub name: String,
+        pub x: usize,
+        pub y: usize,
+        pub width: usize,
+        pub height: usize,
+        pub kind: MapObjectKind,
+    }
+
+    #[derive(Deserialize)]
+    pub enum MapObjectKind {
+        Door {
+            door_id: usize,
+            to_map: MapName,
+            to_id: usize,
+            facing: GameDirection,
+        },
+        Sign {
+            text: String,
+        },
+        Npc {
+            entity_name: EntityName,
+            facing: Option<GameDirection>,
+        },
+        Trigger {
+            event: TriggerEvent,
+            function: String,
+            conditions: Vec<StateCondition>,
+            once: bool,
+        },
+        Boulder,
+        PressurePlate {
+            state: StateName,
+        },
+        Gate {
+            conditions: Vec<StateCondition>,
+        },
+        Pickup {
+            item: ItemId,
+            quantity: usize,
+        },
+    }
+
+    fn upgrade_conditions(conditions: Vec<StateCondition>) -> Vec<super::StateCondition> {
+        conditions
+            .into_iter()
+            .map(StateCondition::upgrade)
+            .collect()
+    }
+
+    impl MapObject {
+        fn upgrade(self) -> super::MapObject {
+            super::MapObject {
+                id: self.id,
+                name: self.name,
+                x: self.x,
+                y: self.y,
+                width: self.width,
+                height: self.height,
+                kind: match self.kind {
+                    MapObjectKind::Door {
+                        door_id,
+                        to_map,
+                        to_id,
+                        facing,
+                    } => super::MapObjectKind::Door {
+                        door_id,
+                        to_map,
+                        to_id,
+                        facing,
+                    },
+                    MapObjectKind::Sign { text } => super::MapObjectKind::Sign { text },
+                    MapObjectKind::Npc {
+                        entity_name,
+                        facing,
+                    } => super::MapObjectKind::Npc {
+                        entity_name: entity_name.upgrade(),
+                        facing,
+                    },
+                    MapObjectKind::Trigger {
+                        event,
+                        function,
+                        conditions,
+                        once,
+                    } => super::MapObjectKind::Trigger {
+                        event,
+                        function,
+                        conditions: upgrade_conditions(conditions),
+                        once,
+                    },
+                    MapObjectKind::Boulder => super::MapObjectKind::Boulder,
+                    MapObjectKind::PressurePlate { state } => super::MapObjectKind::PressurePlate {
+                        state: state.upgrade(),
+                    },
+                    MapObjectKind::Gate { conditions } => super::MapObjectKind::Gate {
+                        conditions: upgrade_conditions(conditions),
+                    },
+                    MapObjectKind::Pickup { item, quantity } => {
+                        super::MapObjectKind::Pickup { item, quantity }
+                    }
+                },
+            }
+        }
+    }
+
+    pub fn read_objects(sections: &Sections) -> GameResult<Vec<super::MapObject>> {
+        let objects: Vec<MapObject> = sections.read(SECTION_OBJECTS)?;
+
+        Ok(objects.into_iter().map(MapObject::upgrade).collect())
+    }
+}
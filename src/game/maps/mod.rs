@@ -1,8 +1,23 @@
+mod chunks;
+mod connections;
+mod format;
+mod objects;
+mod pathfinding;
+
+pub use chunks::ChunkCache;
+pub use connections::{load_connected_maps, step_into_map, MapConnection};
+pub use objects::{MapObject, MapObjectKind};
+pub use pathfinding::{find_path, PathOptions};
+
+use chunks::ChunkSource;
+
 use super::{
     config,
     ecs::{
         components::{CurrentPosition, FacingDirection, MapName, Player, TargetPosition},
-        resources::{CameraBounds, Frame, Tile, TileMap},
+        resources::{
+            CameraBounds, ConnectedMap, Frame, MapRegistry, TileGrid, TileMap, TileMapLayer,
+        },
     },
     error::types::GameResult,
     game_state::GameState,
@@ -10,31 +25,41 @@ use super::{
 };
 use serde::{Deserialize, Serialize};
 use specs::{Entity, Join, WorldExt};
-use std::collections::HashMap;
+use std::{collections::HashMap, io::Read};
 
 pub fn load_map(
     game_state: &mut GameState,
     ctx: &mut ggez::Context,
-    map_file_path: &str,
+    map_name: &MapName,
     entities: &mut HashMap<(usize, usize), Entity>,
-) -> GameResult {
-    let tile_map_definition = TileMapDefinition::load_from_file(ctx, map_file_path)?;
+) -> GameResult<Vec<Entity>> {
+    let (tile_map_definition, connected_maps) = {
+        let map_registry = game_state.world.fetch::<MapRegistry>();
 
-    let tile_map_width = tile_map_definition.width;
-    let tile_map_height = tile_map_definition.height;
+        let tile_map_definition = TileMapDefinition::load(ctx, &map_registry, map_name)?;
+        let connected_maps = load_connected_maps(ctx, &map_registry, &tile_map_definition)?;
 
-    game_state.world.insert(CameraBounds {
-        min_x: 0.,
-        min_y: 0.,
-        max_x: tile_map_width as f32 - config::VIEWPORT_TILES_WIDTH_F32,
-        max_y: tile_map_height as f32 - config::VIEWPORT_TILES_HEIGHT_F32,
-    });
+        (tile_map_definition, connected_maps)
+    };
 
-    let tile_map = tile_map_definition.to_tile_map(ctx, entities)?;
+    game_state
+        .world
+        .insert(tile_map_definition.camera_bounds(&connected_maps));
+
+    let scene_entities = objects::spawn_objects(
+        game_state,
+        ctx,
+        map_name,
+        &tile_map_definition.objects,
+        entities,
+    )?;
+
+    let mut tile_map = tile_map_definition.to_tile_map(ctx, entities)?;
+    tile_map.connected_maps = connected_maps;
 
     game_state.world.insert(tile_map);
 
-    Ok(())
+    Ok(scene_entities)
 }
 
 pub fn dispose_map(game_state: &mut GameState, entities: &[Entity]) -> GameResult {
@@ -51,113 +76,38 @@ pub fn dispose_map(game_state: &mut GameState, entities: &[Entity]) -> GameResul
     Ok(())
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Debug)]
 pub struct TileMapDefinition {
     pub width: usize,
     pub height: usize,
-    pub background: TileLayer,
-    pub overlay: TileLayer,
+    pub atlas: Atlas,
+    pub tiles: Vec<MapTile>,
+    pub layers: Vec<TileLayer>,
+    pub chunks: ChunkSource,
+    pub objects: Vec<MapObject>,
+    pub connections: Vec<MapConnection>,
 }
 
-fn build_spritesheet_from_layer(
-    layer: &TileLayer,
-    ctx: &mut ggez::Context,
-) -> GameResult<(ggez::graphics::spritebatch::SpriteBatch, usize, usize)> {
-    let loaded_images: Vec<GameResult<ggez::graphics::Image>> = layer
-        .tile_sets
-        .iter()
-        .map(|tile_set| ggez::graphics::Image::new(ctx, tile_set.sprite_sheet_filename.to_string()))
-        .collect();
-
-    if let Some(Err(e)) = loaded_images
+fn build_animation(tiles: &[MapTile]) -> HashMap<usize, Frame> {
+    tiles
         .iter()
-        .find(|loaded_image| loaded_image.is_err())
-    {
-        return Err(e.clone());
-    }
-
-    let loaded_images: Vec<ggez::graphics::Image> = loaded_images
-        .into_iter()
-        .map(|loaded_image| loaded_image.unwrap())
-        .collect();
-
-    let canvas_width: u16 = loaded_images
-        .iter()
-        .map(|image| image.width())
-        .max()
-        .expect("No spritesheets loaded?");
-
-    let canvas_height: u16 = loaded_images
-        .iter()
-        .map(|image| image.height())
-        .sum::<u16>();
-
-    let canvas = ggez::graphics::Canvas::new(
-        ctx,
-        canvas_width,
-        canvas_height,
-        ggez::conf::NumSamples::One,
-        ggez::graphics::get_window_color_format(ctx),
-    )?;
-
-    let screen_coords = ggez::graphics::screen_coordinates(ctx);
-
-    ggez::graphics::set_canvas(ctx, Some(&canvas));
-    ggez::graphics::set_screen_coordinates(
-        ctx,
-        [0., 0., canvas_width as f32, canvas_height as f32].into(),
-    )?;
-
-    let mut y_offset = 0.;
-
-    if let Some(Err(e)) = loaded_images
-        .iter()
-        .map(|image| {
-            ggez::graphics::draw(
-                ctx,
-                image,
-                // Canvas images are drawn upside down
-                ggez::graphics::DrawParam::default()
-                    .scale([1., -1.])
-                    .dest([0., y_offset + canvas_height as f32]),
-            )?;
-            y_offset -= image.height() as f32;
-
-            Ok(())
-        })
-        .find(|res: &GameResult| res.is_err())
-    {
-        return Err(e);
-    }
-
-    ggez::graphics::set_canvas(ctx, None);
-    ggez::graphics::set_screen_coordinates(ctx, screen_coords)?;
-
-    let spritesheet_image = canvas.into_inner();
-
-    let width = spritesheet_image.width() as usize / config::TILE_PIXELS_SIZE_USIZE;
-    let height = spritesheet_image.height() as usize / config::TILE_PIXELS_SIZE_USIZE;
-
-    let spritesheet = ggez::graphics::spritebatch::SpriteBatch::new(spritesheet_image);
-
-    Ok((spritesheet, width, height))
-}
-
-fn build_animation_from_layer(layer: TileLayer) -> Vec<Frame> {
-    layer
-        .tile_sets
-        .into_iter()
-        .flat_map(|set| set.tiles)
         .filter(|t| t.animation.is_some())
         .map(|t| {
-            let tile_ids: Vec<usize> = t
-                .animation
-                .unwrap()
+            let frames = t.animation.as_ref().unwrap();
+
+            let tile_ids = frames.iter().map(|frame| frame.tile_id).collect();
+            let durations = frames
                 .iter()
-                .map(|frame| frame.tile_id)
+                .map(|frame| frame.duration_millis as f32 / 1000.)
                 .collect();
 
-            Frame { idx: 0, tile_ids }
+            let offset = t
+                .properties
+                .get("animation_offset")
+                .and_then(TileProperty::as_int)
+                .unwrap_or(0);
+
+            (t.id, Frame::new(tile_ids, durations, offset as f32 / 1000.))
         })
         .collect()
 }
@@ -199,8 +149,8 @@ pub fn find_and_move_player(
 
         current_position.x = position.0 as f32;
         current_position.y = position.1 as f32;
-        target_position.from_x = position.0;
-        target_position.from_y = position.1;
+        target_position.from_x = position.0 as isize;
+        target_position.from_y = position.1 as isize;
         target_position.x = position.0;
         target_position.y = position.1;
         facing_direction.direction = direction;
@@ -210,8 +160,16 @@ pub fn find_and_move_player(
 }
 
 impl TileMapDefinition {
+    pub fn load(
+        ctx: &mut ggez::Context,
+        map_registry: &MapRegistry,
+        map_name: &MapName,
+    ) -> GameResult<Self> {
+        Self::load_from_file(ctx, &map_registry.get(map_name)?.tile_map)
+    }
+
     pub fn load_from_file(ctx: &mut ggez::Context, filename: &str) -> GameResult<Self> {
-        let file = ctx
+        let mut file = ctx
             .filesystem
             .find_vfs(&ctx.filesystem.assets_path)
             .ok_or_else(|| {
@@ -219,12 +177,34 @@ impl TileMapDefinition {
             })?
             .open(&std::path::PathBuf::from(filename))?;
 
-        bincode::deserialize_from(file).or_else(|e| {
-            Err(ggez::GameError::ResourceLoadError(format!(
-                "Couldn't load map binary: {}",
-                e
-            )))
-        })
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+
+        format::read_tile_map_definition(filename, &bytes)
+    }
+
+    // The camera can move over connected maps too, so it doesn't jump when walking between them
+    pub fn camera_bounds(&self, connected_maps: &[ConnectedMap]) -> CameraBounds {
+        let mut min_x = 0;
+        let mut min_y = 0;
+        let mut max_x = self.width as isize;
+        let mut max_y = self.height as isize;
+
+        for connected_map in connected_maps {
+            let (width, height) = connected_map.tile_map.grid.dimensions();
+
+            min_x = min_x.min(connected_map.origin_x);
+            min_y = min_y.min(connected_map.origin_y);
+            max_x = max_x.max(connected_map.origin_x + width as isize);
+            max_y = max_y.max(connected_map.origin_y + height as isize);
+        }
+
+        CameraBounds {
+            min_x: min_x as f32,
+            min_y: min_y as f32,
+            max_x: max_x as f32 - config::VIEWPORT_TILES_WIDTH_F32,
+            max_y: max_y as f32 - config::VIEWPORT_TILES_HEIGHT_F32,
+        }
     }
 
     pub fn to_tile_map(
@@ -232,101 +212,150 @@ impl TileMapDefinition {
         ctx: &mut ggez::Context,
         entities: &mut HashMap<(usize, usize), Entity>,
     ) -> GameResult<TileMap> {
-        let (background_spritesheet, background_width, background_height) =
-            build_spritesheet_from_layer(&self.background, ctx)?;
-
-        let (overlay_spritesheet, overlay_width, overlay_height) =
-            build_spritesheet_from_layer(&self.overlay, ctx)?;
-
-        let tiles = self.build_tiles(entities)?;
-
-        let background_indices = self.background.tile_ids.clone();
-        let overlay_indices = self.overlay.tile_ids.clone();
-
-        let background_animation = build_animation_from_layer(self.background);
-        let overlay_animation = build_animation_from_layer(self.overlay);
+        let atlas_image = ggez::graphics::Image::new(ctx, &self.atlas.image_filename)?;
+        let uvs = self.atlas.uvs();
+        let animation = build_animation(&self.tiles);
+
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| {
+                TileMapLayer::new(layer.name.clone(), layer.draw_order, atlas_image.clone())
+            })
+            .collect();
 
         Ok(TileMap {
-            tiles,
-            background_indices,
-            overlay_indices,
-            background_animation,
-            overlay_animation,
-            background: background_spritesheet,
-            background_width,
-            background_height,
-            overlay: overlay_spritesheet,
-            overlay_width,
-            overlay_height,
+            grid: self.into_grid(entities),
+            layers,
+            uvs,
+            animation,
+            connected_maps: vec![],
             spritesheet_param: ggez::graphics::DrawParam::default(),
             to_draw: vec![],
         })
     }
 
-    pub fn build_tiles(
-        &self,
-        entities: &mut HashMap<(usize, usize), Entity>,
-    ) -> GameResult<Vec<Vec<Tile>>> {
-        let id_tile_types =
-            self.background
-                .tile_sets
-                .iter()
-                .fold(HashMap::new(), |mut map, tile_set| {
-                    tile_set.tiles.iter().for_each(|map_tile| {
-                        map.insert(map_tile.id, map_tile.tile_type.clone());
-                    });
+    pub fn find_door(&self, door_id: usize) -> Option<((usize, usize), GameDirection)> {
+        self.objects.iter().find_map(|object| match &object.kind {
+            MapObjectKind::Door {
+                door_id: id,
+                facing,
+                ..
+            } if *id == door_id => Some(((object.x, object.y), *facing)),
+            _ => None,
+        })
+    }
 
-                    map
-                });
+    // Entities on the map are moved into the grid. No chunks are loaded until the camera is
+    // somewhere
+    pub fn into_grid(self, entities: &mut HashMap<(usize, usize), Entity>) -> TileGrid {
+        let (width, height) = (self.width, self.height);
+
+        let positions: Vec<(usize, usize)> = entities
+            .keys()
+            .filter(|(x, y)| *x < width && *y < height)
+            .copied()
+            .collect();
+
+        let entities = positions
+            .into_iter()
+            .filter_map(|position| Some((position, entities.remove(&position)?)))
+            .collect();
+
+        let tile_properties = self
+            .tiles
+            .into_iter()
+            .filter(|map_tile| !map_tile.properties.is_empty())
+            .map(|map_tile| (map_tile.id, map_tile.properties))
+            .collect();
+
+        TileGrid::new(
+            width,
+            height,
+            ChunkCache::new(self.chunks, width, height),
+            tile_properties,
+            entities,
+        )
+    }
+}
 
-        let mut x_y_tiles = vec![];
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TileLayer {
+    pub name: String,
+    pub draw_order: DrawOrder,
+}
 
-        for y in 0..self.height {
-            let mut x_tiles = vec![];
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawOrder {
+    Below,
+    Above,
+    Sorted,
+}
 
-            for x in 0..self.width {
-                let entity = entities.remove(&(x, y));
+// Every tile a map uses, packed into one image at build time
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Atlas {
+    pub image_filename: String,
+    pub width: usize,
+    pub height: usize,
+    // Indexed by tile id, [x, y, w, h] as fractions of the atlas size
+    pub uvs: Vec<[f32; 4]>,
+}
 
-                x_tiles.push(Tile {
-                    entity,
-                    tile_type: self.background.tile_ids[y * self.width + x]
-                        .and_then(|tile_id| id_tile_types.get(&tile_id).cloned()),
-                })
-            }
+impl Atlas {
+    pub fn uv(&self, tile_id: usize) -> ggez::graphics::Rect {
+        let [x, y, w, h] = self.uvs[tile_id];
 
-            x_y_tiles.push(x_tiles);
-        }
+        ggez::graphics::Rect::new(x, y, w, h)
+    }
 
-        Ok(x_y_tiles)
+    pub fn uvs(&self) -> Vec<ggez::graphics::Rect> {
+        (0..self.uvs.len())
+            .map(|tile_id| self.uv(tile_id))
+            .collect()
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct TileLayer {
-    pub tile_ids: Vec<Option<usize>>,
-    pub tile_sets: Vec<TileSet>,
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum TileProperty {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct TileSet {
-    pub sprite_sheet_filename: String,
-    pub tiles: Vec<MapTile>,
-}
+impl TileProperty {
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub enum TileType {
-    Wall,
-    Water,
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MapTile {
     pub id: usize,
-    pub tile_type: TileType,
+    pub properties: HashMap<String, TileProperty>,
     pub animation: Option<Vec<MapTileAnimationFrame>>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MapTileAnimationFrame {
     pub tile_id: usize,
+    pub duration_millis: u64,
 }
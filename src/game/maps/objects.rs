@@ -0,0 +1,498 @@
+use super::super::{
+    config,
+    ecs::{
+        components::{
+            CurrentPosition, Door, Drawable, EntityName, Footprint, Gate, Id, Interactable, ItemId,
+            MapName, NpcMovement, PressurePlate, Pushable, StateCondition, StateName,
+            TargetPosition, Timer, Trigger, TriggerEvent,
+        },
+        resources::{ItemRegistry, MapRegistry, TileMap},
+    },
+    error::types::GameResult,
+    game_state::GameState,
+    input::types::GameDirection,
+    save::{EntityInstanceDefinition, MapDefinition, Position, SaveData},
+    scenes::{types::SceneBuilder, TextBoxScene},
+};
+use serde::{Deserialize, Serialize};
+use specs::{Builder, Entity, WorldExt};
+use std::{cell::RefCell, collections::HashMap, rc::Rc, sync::Arc};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MapObject {
+    pub id: usize,
+    pub name: String,
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    pub kind: MapObjectKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum MapObjectKind {
+    Door {
+        door_id: usize,
+        to_map: MapName,
+        to_id: usize,
+        facing: GameDirection,
+    },
+    Sign {
+        text: String,
+    },
+    Npc {
+        entity_name: EntityName,
+        facing: Option<GameDirection>,
+    },
+    Trigger {
+        event: TriggerEvent,
+        function: String,
+        conditions: Vec<StateCondition>,
+        once: bool,
+    },
+    Boulder,
+    PressurePlate {
+        state: StateName,
+    },
+    Gate {
+        conditions: Vec<StateCondition>,
+    },
+    Pickup {
+        item: ItemId,
+        quantity: usize,
+    },
+}
+
+impl MapObject {
+    // Point objects in Tiled don't have a size, but still take up the tile they're on
+    fn footprint(&self) -> Footprint {
+        Footprint {
+            width: self.width.max(1),
+            height: self.height.max(1),
+        }
+    }
+}
+
+// Picked up items are gone from the map for good, unless there isn't room for all of them
+fn pick_up(
+    game_state: &mut GameState,
+    entity: Entity,
+    object_id: usize,
+    positions: &[(usize, usize)],
+    item: &ItemId,
+    quantity: usize,
+) -> GameResult<String> {
+    let item_definition = game_state.world.fetch::<ItemRegistry>().get(item)?.clone();
+
+    {
+        let mut save_data = game_state.world.fetch_mut::<SaveData>();
+
+        let inventory = &mut save_data.player.inventory;
+        if inventory.room_for(item, item_definition.max_stack) < quantity {
+            return Ok(format!("There's no room for more {}", item_definition.name));
+        }
+        inventory.add(item, quantity, item_definition.max_stack);
+
+        let map = save_data.player.map.clone();
+        if let Some(map_definition) = save_data.maps.get_mut(&map) {
+            map_definition.picked_up.insert(object_id);
+        }
+    }
+
+    // The entity is deleted with the rest of the map, until then it's just out of the way
+    if let Some(mut tile_map) = game_state.world.try_fetch_mut::<TileMap>() {
+        for &(x, y) in positions {
+            if tile_map.grid.entity(x, y) == Some(entity) {
+                tile_map.grid.set_entity(x, y, None);
+            }
+        }
+    }
+
+    if quantity == 1 {
+        Ok(format!("Found {}!", item_definition.name))
+    } else {
+        Ok(format!("Found {} x{}!", item_definition.name, quantity))
+    }
+}
+
+pub fn spawn_objects(
+    game_state: &mut GameState,
+    ctx: &mut ggez::Context,
+    map_name: &MapName,
+    objects: &[MapObject],
+    entities: &mut HashMap<(usize, usize), Entity>,
+) -> GameResult<Vec<Entity>> {
+    let manifest = game_state
+        .world
+        .fetch::<MapRegistry>()
+        .get(map_name)?
+        .clone();
+
+    // Maps added to the registry after the save was made start out empty
+    let map_save_data = {
+        let mut save_data_r = game_state
+            .world
+            .try_fetch_mut::<SaveData>()
+            .ok_or_else(|| {
+                ggez::GameError::CustomError("SaveData resource not found".to_string())
+            })?;
+
+        save_data_r
+            .maps
+            .entry(map_name.clone())
+            .or_insert_with(|| MapDefinition::new(HashMap::new()))
+            .clone()
+    };
+
+    let mut scene_entities = vec![];
+
+    for object in objects {
+        match &object.kind {
+            MapObjectKind::Door {
+                door_id,
+                to_map,
+                to_id,
+                ..
+            } => {
+                let door_entity = game_state
+                    .world
+                    .create_entity()
+                    .with(Id::new(&object.name))
+                    .with(Door {
+                        id: *door_id,
+                        to_map: to_map.clone(),
+                        to_id: *to_id,
+                    })
+                    .with(object.footprint())
+                    .build();
+
+                for position in object.footprint().positions(object.x, object.y) {
+                    entities.insert(position, door_entity);
+                }
+                scene_entities.push(door_entity);
+            }
+            MapObjectKind::Sign { text } => {
+                let text = text.clone();
+
+                let sign_entity = game_state
+                    .world
+                    .create_entity()
+                    .with(Id::new(&object.name))
+                    .with(Interactable {
+                        handler: Box::new(move |_, target_entity| {
+                            let text = text.clone();
+
+                            let scene_builder: SceneBuilder = Box::new(move |_, _| {
+                                let scene = TextBoxScene::new(target_entity, &text);
+                                Ok(Rc::new(RefCell::new(scene)))
+                            });
+
+                            Some(scene_builder)
+                        }),
+                    })
+                    .with(object.footprint())
+                    .build();
+
+                for position in object.footprint().positions(object.x, object.y) {
+                    entities.insert(position, sign_entity);
+                }
+                scene_entities.push(sign_entity);
+            }
+            MapObjectKind::Npc {
+                entity_name,
+                facing,
+            } => {
+                let map_entity = manifest
+                    .entities
+                    .iter()
+                    .find(|entity| entity.name == *entity_name);
+
+                // Saved NPCs keep where they were left, otherwise they start at their spawn
+                let npc_instance = match map_save_data.entity_instances.get(entity_name) {
+                    Some(entity_instance) => entity_instance.clone(),
+                    None => {
+                        let entity_instance = EntityInstanceDefinition {
+                            position: Position {
+                                x: object.x,
+                                y: object.y,
+                                facing: *facing,
+                            },
+                            dialog_id: map_entity.map_or(0, |entity| entity.dialog_id),
+                        };
+
+                        if let Some(map_definition) = game_state
+                            .world
+                            .fetch_mut::<SaveData>()
+                            .maps
+                            .get_mut(map_name)
+                        {
+                            map_definition
+                                .entity_instances
+                                .insert(entity_name.clone(), entity_instance.clone());
+                        }
+
+                        entity_instance
+                    }
+                };
+                let npc_position = (npc_instance.position.x, npc_instance.position.y);
+                let npc_direction = npc_instance.position.facing;
+
+                let npc_entity = entity_name.new_entity(
+                    game_state,
+                    ctx,
+                    npc_position,
+                    npc_direction.unwrap_or_else(|| GameDirection::Down),
+                )?;
+
+                // Moving NPCs stay around their spawn
+                if let Some(movement) = map_entity.and_then(|entity| entity.movement.clone()) {
+                    game_state
+                        .world
+                        .write_storage::<NpcMovement>()
+                        .insert(npc_entity, NpcMovement::new(movement, (object.x, object.y)))
+                        .map_err(|e| {
+                            ggez::GameError::CustomError(format!(
+                                "Couldn't add movement to {}: {}",
+                                entity_name, e
+                            ))
+                        })?;
+                }
+
+                if let Some(script) = map_entity.and_then(|entity| entity.script.as_ref()) {
+                    game_state
+                        .world
+                        .write_storage::<Interactable>()
+                        .insert(npc_entity, Interactable::from_script(script))
+                        .map_err(|e| {
+                            ggez::GameError::CustomError(format!(
+                                "Couldn't add script {} to {}: {}",
+                                script, entity_name, e
+                            ))
+                        })?;
+                }
+
+                let footprint = object.footprint();
+
+                for position in footprint.positions(npc_position.0, npc_position.1) {
+                    entities.insert(position, npc_entity);
+                }
+
+                game_state
+                    .world
+                    .write_storage::<Footprint>()
+                    .insert(npc_entity, footprint)
+                    .map_err(|e| {
+                        ggez::GameError::CustomError(format!(
+                            "Couldn't add footprint to {:?}: {}",
+                            entity_name, e
+                        ))
+                    })?;
+
+                scene_entities.push(npc_entity);
+            }
+            MapObjectKind::Trigger {
+                event,
+                function,
+                conditions,
+                once,
+            } => {
+                let footprint = object.footprint();
+
+                let trigger_entity = game_state
+                    .world
+                    .create_entity()
+                    .with(Id::new(&object.name))
+                    .with(Trigger {
+                        id: object.id,
+                        x: object.x,
+                        y: object.y,
+                        width: footprint.width,
+                        height: footprint.height,
+                        event: *event,
+                        function: function.clone(),
+                        conditions: conditions.clone(),
+                        once: *once,
+                    })
+                    .build();
+
+                scene_entities.push(trigger_entity);
+            }
+            MapObjectKind::Boulder => {
+                // Pushed boulders stay where they were left
+                let (x, y) = map_save_data
+                    .pushed
+                    .get(&object.id)
+                    .cloned()
+                    .unwrap_or((object.x, object.y));
+
+                let boulder_entity = game_state
+                    .world
+                    .create_entity()
+                    .with(Id::new(&object.name))
+                    .with(Pushable { id: object.id })
+                    .with(Drawable {
+                        drawable: Arc::new(ggez::graphics::Mesh::new_circle(
+                            ctx,
+                            ggez::graphics::DrawMode::fill(),
+                            [
+                                config::TILE_PIXELS_SIZE_F32 / 2.,
+                                config::TILE_PIXELS_SIZE_F32 / 2.,
+                            ],
+                            config::TILE_PIXELS_SIZE_F32 / 2. - 1.,
+                            0.5,
+                            ggez::graphics::Color::from_rgb(110, 105, 100),
+                        )?),
+                        draw_params: ggez::graphics::DrawParam::default(),
+                        offset_y: 0.,
+                    })
+                    .with(CurrentPosition {
+                        x: x as f32,
+                        y: y as f32,
+                    })
+                    .with(TargetPosition {
+                        x,
+                        y,
+                        from_x: x as isize,
+                        from_y: y as isize,
+                        is_moving: false,
+                        is_hopping: false,
+                    })
+                    .with(Timer {
+                        duration: config::WALK_SECONDS_PER_TILE,
+                        repeating: true,
+                        elapsed: 0.0,
+                        finished: true,
+                        should_tick: false,
+                    })
+                    .build();
+
+                entities.insert((x, y), boulder_entity);
+                scene_entities.push(boulder_entity);
+            }
+            MapObjectKind::PressurePlate { state } => {
+                let plate_entity = game_state
+                    .world
+                    .create_entity()
+                    .with(Id::new(&object.name))
+                    .with(PressurePlate {
+                        x: object.x,
+                        y: object.y,
+                        state: state.clone(),
+                    })
+                    .build();
+
+                scene_entities.push(plate_entity);
+            }
+            MapObjectKind::Gate { conditions } => {
+                let footprint = object.footprint();
+
+                let gate = Gate {
+                    x: object.x,
+                    y: object.y,
+                    conditions: conditions.clone(),
+                };
+                let is_open = gate.is_open(&game_state.world.fetch::<SaveData>(), map_name);
+
+                let gate_entity = game_state
+                    .world
+                    .create_entity()
+                    .with(Id::new(&object.name))
+                    .with(Drawable {
+                        drawable: Arc::new(ggez::graphics::Mesh::new_rectangle(
+                            ctx,
+                            ggez::graphics::DrawMode::fill(),
+                            ggez::graphics::Rect::new(
+                                0.,
+                                0.,
+                                footprint.width as f32 * config::TILE_PIXELS_SIZE_F32,
+                                footprint.height as f32 * config::TILE_PIXELS_SIZE_F32,
+                            ),
+                            ggez::graphics::Color::from_rgb(90, 60, 30),
+                        )?),
+                        draw_params: ggez::graphics::DrawParam::default(),
+                        offset_y: 0.,
+                    })
+                    .with(CurrentPosition {
+                        x: object.x as f32,
+                        y: object.y as f32,
+                    })
+                    .with(gate)
+                    .with(footprint.clone())
+                    .build();
+
+                // Open gates aren't in the way, and aren't drawn
+                if !is_open {
+                    for position in footprint.positions(object.x, object.y) {
+                        entities.insert(position, gate_entity);
+                    }
+                }
+                scene_entities.push(gate_entity);
+            }
+            MapObjectKind::Pickup { item, quantity } => {
+                if map_save_data.picked_up.contains(&object.id) {
+                    continue;
+                }
+
+                let object_id = object.id;
+                let positions: Vec<_> = object.footprint().positions(object.x, object.y).collect();
+                let item = item.clone();
+                let quantity = *quantity;
+
+                let pickup_entity = game_state
+                    .world
+                    .create_entity()
+                    .with(Id::new(&object.name))
+                    .with(Drawable {
+                        drawable: Arc::new(ggez::graphics::Mesh::new_circle(
+                            ctx,
+                            ggez::graphics::DrawMode::fill(),
+                            [
+                                config::TILE_PIXELS_SIZE_F32 / 2.,
+                                config::TILE_PIXELS_SIZE_F32 / 2.,
+                            ],
+                            config::TILE_PIXELS_SIZE_F32 / 4.,
+                            0.5,
+                            ggez::graphics::Color::from_rgb(200, 40, 40),
+                        )?),
+                        draw_params: ggez::graphics::DrawParam::default(),
+                        offset_y: 0.,
+                    })
+                    .with(CurrentPosition {
+                        x: object.x as f32,
+                        y: object.y as f32,
+                    })
+                    .with(Interactable {
+                        handler: Box::new(move |_, target_entity| {
+                            let positions = positions.clone();
+                            let item = item.clone();
+
+                            let scene_builder: SceneBuilder = Box::new(move |game_state, _| {
+                                let text = pick_up(
+                                    game_state,
+                                    target_entity,
+                                    object_id,
+                                    &positions,
+                                    &item,
+                                    quantity,
+                                )?;
+
+                                let scene = TextBoxScene::new(target_entity, &text);
+                                Ok(Rc::new(RefCell::new(scene)))
+                            });
+
+                            Some(scene_builder)
+                        }),
+                    })
+                    .with(object.footprint())
+                    .build();
+
+                for position in object.footprint().positions(object.x, object.y) {
+                    entities.insert(position, pickup_entity);
+                }
+                scene_entities.push(pickup_entity);
+            }
+        }
+    }
+
+    Ok(scene_entities)
+}
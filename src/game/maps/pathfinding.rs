@@ -0,0 +1,360 @@
+use super::super::{
+    ecs::{
+        components::{Door, Footprint},
+        resources::TileGrid,
+    },
+    input::types::GameDirection,
+};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+const DIRECTIONS: [GameDirection; 4] = [
+    GameDirection::Up,
+    GameDirection::Down,
+    GameDirection::Left,
+    GameDirection::Right,
+];
+
+#[derive(Default)]
+pub struct PathOptions<'a, 'b> {
+    // Whoever walks the path. Tiles they're already in don't get in their way, and all of their
+    // footprint has to fit at every step. Without one, the path is for something a tile big
+    pub mover: Option<(specs::Entity, &'a Footprint)>,
+    // Walk through the player and NPCs as if they weren't there, e.g. for paths planned ahead of
+    // when they're walked
+    pub ignore_entities: bool,
+    // Hop down ledges like the player does. NPCs never step onto ledges, so their paths can't
+    // either
+    pub allow_ledges: bool,
+    // Doors take up their tile like any other entity, so the path can only end on one if it knows
+    // which entities are doors
+    pub doors: Option<&'a specs::ReadStorage<'b, Door>>,
+}
+
+fn in_bounds(grid: &TileGrid, x: isize, y: isize) -> Option<(usize, usize)> {
+    let (width, height) = grid.dimensions();
+
+    if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+        return None;
+    }
+
+    Some((x as usize, y as usize))
+}
+
+// Where a step ends up, if it can be taken, the same way the player moves
+fn step(
+    grid: &TileGrid,
+    position: (usize, usize),
+    direction: GameDirection,
+    to: (usize, usize),
+    options: &PathOptions,
+) -> Option<(usize, usize)> {
+    let single_tile = Footprint::default();
+    let (mover, footprint) = match options.mover {
+        Some((entity, footprint)) => (Some(entity), footprint),
+        None => (None, &single_tile),
+    };
+
+    let (direction_x, direction_y) = direction.to_xy();
+
+    let (mut x, mut y) = in_bounds(
+        grid,
+        position.0 as isize + direction_x,
+        position.1 as isize + direction_y,
+    )?;
+
+    // Ledges can only be hopped down in their direction, landing on the tile past them. Only
+    // single tiles hop, the same as the player
+    let mut is_hop = false;
+    if let Some(ledge_direction) = grid.ledge_direction(x, y) {
+        if !options.allow_ledges
+            || ledge_direction != direction
+            || footprint.width != 1
+            || footprint.height != 1
+        {
+            return None;
+        }
+
+        let landing = in_bounds(grid, x as isize + direction_x, y as isize + direction_y)?;
+        x = landing.0;
+        y = landing.1;
+        is_hop = true;
+    }
+
+    let (width, height) = grid.dimensions();
+    if x + footprint.width > width || y + footprint.height > height {
+        return None;
+    }
+
+    for (tile_x, tile_y) in footprint.positions(x, y) {
+        if grid.blocks_movement(tile_x, tile_y) {
+            return None;
+        }
+
+        if !is_hop && grid.ledge_direction(tile_x, tile_y).is_some() {
+            return None;
+        }
+
+        if let Some(entity) = grid.entity(tile_x, tile_y) {
+            let is_door_goal = (x, y) == to
+                && options
+                    .doors
+                    .map_or(false, |door_c| door_c.get(entity).is_some());
+
+            if Some(entity) != mover && !options.ignore_entities && !is_door_goal {
+                return None;
+            }
+        }
+    }
+
+    Some((x, y))
+}
+
+// Shortest path between two tiles, as the directions to walk in. Hopping a ledge is a single step
+// that moves two tiles
+pub fn find_path(
+    grid: &TileGrid,
+    from: (usize, usize),
+    to: (usize, usize),
+    options: &PathOptions,
+) -> Option<Vec<GameDirection>> {
+    let (width, height) = grid.dimensions();
+
+    if from.0 >= width || from.1 >= height || to.0 >= width || to.1 >= height {
+        return None;
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<(usize, usize), ((usize, usize), GameDirection)> = HashMap::new();
+    let mut cost_so_far: HashMap<(usize, usize), usize> = HashMap::new();
+
+    open.push(Reverse((distance_between(from, to), from)));
+    cost_so_far.insert(from, 0);
+
+    while let Some(Reverse((_, position))) = open.pop() {
+        if position == to {
+            let mut path = vec![];
+            let mut position = to;
+
+            while let Some(&(previous, direction)) = came_from.get(&position) {
+                path.push(direction);
+                position = previous;
+            }
+
+            path.reverse();
+            return Some(path);
+        }
+
+        let cost = cost_so_far[&position];
+
+        for &direction in DIRECTIONS.iter() {
+            let next = match step(grid, position, direction, to, options) {
+                Some(next) => next,
+                None => continue,
+            };
+
+            // Hops cost the two tiles they cover
+            let next_cost = cost + distance_between(position, next);
+
+            if cost_so_far
+                .get(&next)
+                .map_or(true, |&previous_cost| next_cost < previous_cost)
+            {
+                cost_so_far.insert(next, next_cost);
+                came_from.insert(next, (position, direction));
+                open.push(Reverse((next_cost + distance_between(next, to), next)));
+            }
+        }
+    }
+
+    None
+}
+
+fn distance_between(from: (usize, usize), to: (usize, usize)) -> usize {
+    (from.0 as isize - to.0 as isize).abs() as usize
+        + (from.1 as isize - to.1 as isize).abs() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{super::ecs::components::Footprint, format, ChunkCache, ChunkSource, TileProperty},
+        find_path, GameDirection, PathOptions, TileGrid,
+    };
+    use specs::{Builder, WorldExt};
+    use std::collections::HashMap;
+
+    fn load_grid(name: &str) -> TileGrid {
+        let filename = format!("/bin/maps/{}", name);
+        let bytes =
+            std::fs::read(format!("{}/assets{}", env!("CARGO_MANIFEST_DIR"), filename)).unwrap();
+
+        let mut grid = format::read_tile_map_definition(&filename, &bytes)
+            .unwrap()
+            .into_grid(&mut HashMap::new());
+        grid.load_all().unwrap();
+
+        grid
+    }
+
+    // A column of open tiles, with a ledge facing down in the second one
+    fn ledge_grid() -> TileGrid {
+        let chunks = ChunkSource::from_layers(1, 4, &[vec![None, Some(0), None, None]]);
+
+        let mut tile_properties = HashMap::new();
+        tile_properties.insert(
+            0,
+            vec![(
+                "ledge_direction".to_string(),
+                TileProperty::String("Down".to_string()),
+            )]
+            .into_iter()
+            .collect(),
+        );
+
+        let mut grid = TileGrid::new(
+            1,
+            4,
+            ChunkCache::new(chunks, 1, 4),
+            tile_properties,
+            HashMap::new(),
+        );
+        grid.load_all().unwrap();
+
+        grid
+    }
+
+    fn walk(from: (usize, usize), path: &[GameDirection]) -> (usize, usize) {
+        path.iter().fold(from, |(x, y), direction| {
+            let (direction_x, direction_y) = direction.to_xy();
+            (
+                (x as isize + direction_x) as usize,
+                (y as isize + direction_y) as usize,
+            )
+        })
+    }
+
+    #[test]
+    fn walks_straight_across_an_open_row() {
+        let grid = load_grid("pallet_town");
+
+        let path = find_path(&grid, (3, 2), (22, 2), &PathOptions::default()).unwrap();
+
+        assert_eq!(path, vec![GameDirection::Right; 19]);
+    }
+
+    #[test]
+    fn walks_around_walls() {
+        let grid = load_grid("pallet_town");
+
+        // The sign's wall is in the way, so it's three tiles out and back on either side
+        let path = find_path(&grid, (9, 5), (9, 7), &PathOptions::default()).unwrap();
+
+        assert_eq!(path.len(), 8);
+        assert_eq!(walk((9, 5), &path), (9, 7));
+    }
+
+    #[test]
+    fn finds_the_way_out_of_a_corridor() {
+        let grid = load_grid("varrock");
+
+        let path = find_path(&grid, (10, 10), (13, 1), &PathOptions::default()).unwrap();
+
+        assert_eq!(path.len(), 12);
+        assert_eq!(walk((10, 10), &path), (13, 1));
+    }
+
+    #[test]
+    fn has_no_path_into_walls() {
+        let grid = load_grid("varrock");
+
+        assert_eq!(
+            find_path(&grid, (3, 2), (0, 0), &PathOptions::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn big_footprints_only_fit_through_wide_gaps() {
+        let mut grid = load_grid("pallet_town");
+        let mut world = specs::World::new();
+        let entity = world.create_entity().build();
+        let footprint = Footprint {
+            width: 2,
+            height: 2,
+        };
+
+        let options = PathOptions {
+            mover: Some((entity, &footprint)),
+            ..PathOptions::default()
+        };
+
+        // The way out at the top is a tile wide
+        assert_eq!(find_path(&grid, (13, 2), (13, 0), &options), None);
+        assert_eq!(
+            find_path(&grid, (13, 2), (13, 0), &PathOptions::default()),
+            Some(vec![GameDirection::Up; 2])
+        );
+
+        // Its own tiles don't get in its way
+        grid.move_entity(entity, (5, 3), (5, 3), &footprint);
+        assert_eq!(
+            find_path(&grid, (5, 3), (7, 3), &options),
+            Some(vec![GameDirection::Right; 2])
+        );
+    }
+
+    #[test]
+    fn only_hops_ledges_when_allowed() {
+        let grid = ledge_grid();
+
+        let hopping = PathOptions {
+            allow_ledges: true,
+            ..PathOptions::default()
+        };
+
+        assert_eq!(
+            find_path(&grid, (0, 0), (0, 2), &hopping),
+            Some(vec![GameDirection::Down])
+        );
+        assert_eq!(find_path(&grid, (0, 2), (0, 0), &hopping), None);
+        assert_eq!(
+            find_path(&grid, (0, 0), (0, 2), &PathOptions::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn only_walks_through_loaded_chunks() {
+        // An open row four chunks long
+        let chunks = ChunkSource::from_layers(64, 1, &[vec![Some(0); 64]]);
+        let mut grid = TileGrid::new(
+            64,
+            1,
+            ChunkCache::new(chunks, 64, 1),
+            HashMap::new(),
+            HashMap::new(),
+        );
+
+        // The first chunk and the one past it
+        grid.load_around(0, 0, 1, 1).unwrap();
+        assert_eq!(
+            find_path(&grid, (0, 0), (31, 0), &PathOptions::default()),
+            Some(vec![GameDirection::Right; 31])
+        );
+        assert_eq!(
+            find_path(&grid, (0, 0), (40, 0), &PathOptions::default()),
+            None
+        );
+
+        // Moving on drops the first chunk
+        grid.load_around(40, 0, 41, 1).unwrap();
+        assert!(grid.blocks_movement(0, 0));
+        assert_eq!(
+            find_path(&grid, (20, 0), (60, 0), &PathOptions::default()),
+            Some(vec![GameDirection::Right; 40])
+        );
+    }
+}
@@ -6,11 +6,14 @@ mod events;
 mod game;
 mod game_loop;
 mod game_state;
+#[cfg(all(debug_assertions, feature = "hot-reload"))]
+mod hot_reload;
 mod input;
 mod maps;
 mod render;
 mod save;
 mod scenes;
+mod scripting;
 mod world;
 
 use super::{config, error, filesystem, utils};
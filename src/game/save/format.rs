@@ -0,0 +1,504 @@
+// Reads and writes save and meta files, upgrading older layouts as they're read
+use super::super::{
+    ecs::components::{ChoiceName, EntityName, MapName, QuestName, StateName, TaskName},
+    error::types::GameResult,
+};
+use super::{
+    EntityInstanceDefinition, Inventory, MapDefinition, MetaSaveData, PlayerDefinition, Position,
+    QuestDefinition, SaveData, TaskStatus, WorldDefinition,
+};
+use bincode::Options;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    hash::Hash,
+};
+
+const MAGIC: [u8; 8] = *b"RGPSAVE\0";
+const VERSION: u32 = 2;
+
+const HEADER_LEN: usize = 8 + 4;
+
+fn load_error(filename: &str, message: String) -> ggez::GameError {
+    ggez::GameError::CustomError(format!("Couldn't load save {}: {}", filename, message))
+}
+
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .deserialize(bytes)
+        .ok()
+}
+
+fn encode<T: Serialize>(value: &T) -> GameResult<Vec<u8>> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+
+    bincode::serialize_into(&mut bytes, value)
+        .map_err(|e| ggez::GameError::CustomError(format!("Couldn't write save: {}", e)))?;
+
+    Ok(bytes)
+}
+
+// Older layouts are upgraded by the reader for them
+fn read<T: DeserializeOwned>(
+    filename: &str,
+    bytes: &[u8],
+    read_unversioned: fn(&[u8]) -> Option<T>,
+    read_v1: fn(&[u8]) -> Option<T>,
+) -> GameResult<T> {
+    if bytes.len() < HEADER_LEN || bytes[0..8] != MAGIC {
+        return read_unversioned(bytes).ok_or_else(|| {
+            load_error(
+                filename,
+                "It isn't a save this version of the game can read".to_string(),
+            )
+        });
+    }
+
+    let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+    let body = &bytes[HEADER_LEN..];
+
+    let value = match version {
+        1 => read_v1(body),
+        VERSION => decode(body),
+        _ => {
+            return Err(load_error(
+                filename,
+                format!(
+                    "Save format version {} isn't supported, this build reads versions 1 to {}",
+                    version, VERSION
+                ),
+            ))
+        }
+    };
+
+    value.ok_or_else(|| {
+        load_error(
+            filename,
+            "It doesn't match its save format version, the file is corrupt".to_string(),
+        )
+    })
+}
+
+fn upgrade_set<T, U: Eq + Hash>(set: HashSet<T>, upgrade: fn(T) -> U) -> HashSet<U> {
+    set.into_iter().map(upgrade).collect()
+}
+
+fn upgrade_keys<K, U: Eq + Hash, V>(map: HashMap<K, V>, upgrade: fn(K) -> U) -> HashMap<U, V> {
+    map.into_iter()
+        .map(|(key, value)| (upgrade(key), value))
+        .collect()
+}
+
+// Saves from before they had a header were all written with the layout below
+fn read_unversioned_save_data(bytes: &[u8]) -> Option<SaveData> {
+    decode(bytes).map(enum_map_names::SaveData::upgrade)
+}
+
+fn read_unversioned_meta_save_data(bytes: &[u8]) -> Option<MetaSaveData> {
+    decode(bytes).map(enum_map_names::MetaSaveData::upgrade)
+}
+
+fn read_v1_save_data(bytes: &[u8]) -> Option<SaveData> {
+    decode(bytes).map(enum_names::SaveData::upgrade)
+}
+
+pub fn read_save_data(filename: &str, bytes: &[u8]) -> GameResult<SaveData> {
+    read(
+        filename,
+        bytes,
+        read_unversioned_save_data,
+        read_v1_save_data,
+    )
+}
+
+// Meta saves have no quest, state or entity names in them, so version 1 is read as it is
+pub fn read_meta_save_data(filename: &str, bytes: &[u8]) -> GameResult<MetaSaveData> {
+    read(filename, bytes, read_unversioned_meta_save_data, decode)
+}
+
+pub fn write_save_data(save_data: &SaveData) -> GameResult<Vec<u8>> {
+    encode(save_data)
+}
+
+pub fn write_meta_save_data(meta_save_data: &MetaSaveData) -> GameResult<Vec<u8>> {
+    encode(meta_save_data)
+}
+
+// Map names used to be an enum, so saves stored the variant index, and so were the names below
+// which only had placeholder variants back then. Quests didn't keep track of who was talked to,
+// and there were no triggers, pushable objects or items
+mod enum_map_names {
+    use super::*;
+
+    #[derive(Deserialize, Hash, PartialEq, Eq)]
+    pub enum MapName {
+        PalletTown,
+        Varrock,
+    }
+
+    impl MapName {
+        fn upgrade(self) -> super::MapName {
+            match self {
+                Self::PalletTown => super::MapName::new("PalletTown"),
+                Self::Varrock => super::MapName::new("Varrock"),
+            }
+        }
+    }
+
+    #[derive(Deserialize, Hash, PartialEq, Eq)]
+    pub enum EntityName {
+        WiseOldMan,
+    }
+
+    impl EntityName {
+        fn upgrade(self) -> super::EntityName {
+            match self {
+                Self::WiseOldMan => super::EntityName::new("WiseOldMan"),
+            }
+        }
+    }
+
+    #[derive(Deserialize, Hash, PartialEq, Eq)]
+    pub enum QuestName {
+        TestQuest,
+    }
+
+    impl QuestName {
+        fn upgrade(self) -> super::QuestName {
+            match self {
+                Self::TestQuest => super::QuestName::new("TestQuest"),
+            }
+        }
+    }
+
+    #[derive(Deserialize, Hash, PartialEq, Eq)]
+    pub enum TaskName {
+        TestTask,
+    }
+
+    impl TaskName {
+        fn upgrade(self) -> super::TaskName {
+            match self {
+                Self::TestTask => super::TaskName::new("TestTask"),
+            }
+        }
+    }
+
+    #[derive(Deserialize, Hash, PartialEq, Eq)]
+    pub enum ChoiceName {
+        TestChoice,
+    }
+
+    impl ChoiceName {
+        fn upgrade(self) -> super::ChoiceName {
+            match self {
+                Self::TestChoice => super::ChoiceName::new("TestChoice"),
+            }
+        }
+    }
+
+    #[derive(Deserialize, Hash, PartialEq, Eq)]
+    pub enum StateName {
+        TestState,
+    }
+
+    impl StateName {
+        fn upgrade(self) -> super::StateName {
+            match self {
+                Self::TestState => super::StateName::new("TestState"),
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct QuestDefinition {
+        pub tasks: HashMap<TaskName, TaskStatus>,
+        pub choices: HashMap<ChoiceName, bool>,
+    }
+
+    impl QuestDefinition {
+        fn upgrade(self) -> super::QuestDefinition {
+            super::QuestDefinition {
+                tasks: upgrade_keys(self.tasks, TaskName::upgrade),
+                choices: upgrade_keys(self.choices, ChoiceName::upgrade),
+                talked_to: HashMap::new(),
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct MapDefinition {
+        pub entity_instances: HashMap<EntityName, EntityInstanceDefinition>,
+        pub states: HashSet<StateName>,
+    }
+
+    impl MapDefinition {
+        fn upgrade(self) -> super::MapDefinition {
+            super::MapDefinition {
+                entity_instances: upgrade_keys(self.entity_instances, EntityName::upgrade),
+                states: upgrade_set(self.states, StateName::upgrade),
+                triggered: HashSet::new(),
+                pushed: HashMap::new(),
+                picked_up: HashSet::new(),
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct PlayerDefinition {
+        pub map: MapName,
+        pub position: Position,
+        pub journal: HashMap<QuestName, QuestDefinition>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct WorldDefinition {
+        pub states: HashSet<StateName>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct SaveData {
+        pub player: PlayerDefinition,
+        pub world: WorldDefinition,
+        pub maps: HashMap<MapName, MapDefinition>,
+        pub entity_states: HashMap<EntityName, HashSet<StateName>>,
+    }
+
+    impl SaveData {
+        pub fn upgrade(self) -> super::SaveData {
+            super::SaveData {
+                player: super::PlayerDefinition {
+                    map: self.player.map.upgrade(),
+                    position: self.player.position,
+                    journal: self
+                        .player
+                        .journal
+                        .into_iter()
+                        .map(|(quest, quest_definition)| {
+                            (quest.upgrade(), quest_definition.upgrade())
+                        })
+                        .collect(),
+                    inventory: Inventory::default(),
+                },
+                world: super::WorldDefinition {
+                    states: upgrade_set(self.world.states, StateName::upgrade),
+                },
+                maps: self
+                    .maps
+                    .into_iter()
+                    .map(|(map, map_definition)| (map.upgrade(), map_definition.upgrade()))
+                    .collect(),
+                entity_states: self
+                    .entity_states
+                    .into_iter()
+                    .map(|(entity, states)| {
+                        (entity.upgrade(), upgrade_set(states, StateName::upgrade))
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct MetaSaveData {
+        pub name: String,
+        pub current_map: MapName,
+        pub seconds_played: usize,
+        pub finished: bool,
+    }
+
+    impl MetaSaveData {
+        pub fn upgrade(self) -> super::MetaSaveData {
+            super::MetaSaveData {
+                name: self.name,
+                current_map: self.current_map.upgrade(),
+                seconds_played: self.seconds_played,
+                finished: self.finished,
+            }
+        }
+    }
+}
+
+// Version 1 still had entity, quest, task, choice and state names as enums, so saves stored the
+// variant index
+mod enum_names {
+    use super::*;
+
+    #[derive(Deserialize, Hash, PartialEq, Eq)]
+    pub enum EntityName {
+        WiseOldMan,
+    }
+
+    impl EntityName {
+        fn upgrade(self) -> super::EntityName {
+            match self {
+                Self::WiseOldMan => super::EntityName::new("WiseOldMan"),
+            }
+        }
+    }
+
+    #[derive(Deserialize, Hash, PartialEq, Eq)]
+    pub enum QuestName {
+        WiseOldMansErrand,
+    }
+
+    impl QuestName {
+        fn upgrade(self) -> super::QuestName {
+            match self {
+                Self::WiseOldMansErrand => super::QuestName::new("WiseOldMansErrand"),
+            }
+        }
+    }
+
+    #[derive(Deserialize, Hash, PartialEq, Eq)]
+    pub enum TaskName {
+        OpenTheGate,
+        BringPotions,
+        ReportBack,
+    }
+
+    impl TaskName {
+        fn upgrade(self) -> super::TaskName {
+            match self {
+                Self::OpenTheGate => super::TaskName::new("OpenTheGate"),
+                Self::BringPotions => super::TaskName::new("BringPotions"),
+                Self::ReportBack => super::TaskName::new("ReportBack"),
+            }
+        }
+    }
+
+    #[derive(Deserialize, Hash, PartialEq, Eq)]
+    pub enum ChoiceName {
+        TestChoice,
+    }
+
+    impl ChoiceName {
+        fn upgrade(self) -> super::ChoiceName {
+            match self {
+                Self::TestChoice => super::ChoiceName::new("TestChoice"),
+            }
+        }
+    }
+
+    #[derive(Deserialize, Hash, PartialEq, Eq)]
+    pub enum StateName {
+        TestState,
+        MetWiseOldMan,
+        VisitedPalletTown,
+        PalletTownPlatePressed,
+    }
+
+    impl StateName {
+        fn upgrade(self) -> super::StateName {
+            match self {
+                Self::TestState => super::StateName::new("TestState"),
+                Self::MetWiseOldMan => super::StateName::new("MetWiseOldMan"),
+                Self::VisitedPalletTown => super::StateName::new("VisitedPalletTown"),
+                Self::PalletTownPlatePressed => super::StateName::new("PalletTownPlatePressed"),
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct QuestDefinition {
+        pub tasks: HashMap<TaskName, TaskStatus>,
+        pub choices: HashMap<ChoiceName, bool>,
+        pub talked_to: HashMap<TaskName, HashSet<EntityName>>,
+    }
+
+    impl QuestDefinition {
+        fn upgrade(self) -> super::QuestDefinition {
+            super::QuestDefinition {
+                tasks: upgrade_keys(self.tasks, TaskName::upgrade),
+                choices: upgrade_keys(self.choices, ChoiceName::upgrade),
+                talked_to: self
+                    .talked_to
+                    .into_iter()
+                    .map(|(task, entities)| {
+                        (task.upgrade(), upgrade_set(entities, EntityName::upgrade))
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct MapDefinition {
+        pub entity_instances: HashMap<EntityName, EntityInstanceDefinition>,
+        pub states: HashSet<StateName>,
+        pub triggered: HashSet<usize>,
+        pub pushed: HashMap<usize, (usize, usize)>,
+        pub picked_up: HashSet<usize>,
+    }
+
+    impl MapDefinition {
+        fn upgrade(self) -> super::MapDefinition {
+            super::MapDefinition {
+                entity_instances: upgrade_keys(self.entity_instances, EntityName::upgrade),
+                states: upgrade_set(self.states, StateName::upgrade),
+                triggered: self.triggered,
+                pushed: self.pushed,
+                picked_up: self.picked_up,
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct PlayerDefinition {
+        pub map: MapName,
+        pub position: Position,
+        pub journal: HashMap<QuestName, QuestDefinition>,
+        pub inventory: Inventory,
+    }
+
+    #[derive(Deserialize)]
+    pub struct WorldDefinition {
+        pub states: HashSet<StateName>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct SaveData {
+        pub player: PlayerDefinition,
+        pub world: WorldDefinition,
+        pub maps: HashMap<MapName, MapDefinition>,
+        pub entity_states: HashMap<EntityName, HashSet<StateName>>,
+    }
+
+    impl SaveData {
+        pub fn upgrade(self) -> super::SaveData {
+            super::SaveData {
+                player: super::PlayerDefinition {
+                    map: self.player.map,
+                    position: self.player.position,
+                    journal: self
+                        .player
+                        .journal
+                        .into_iter()
+                        .map(|(quest, quest_definition)| {
+                            (quest.upgrade(), quest_definition.upgrade())
+                        })
+                        .collect(),
+                    inventory: self.player.inventory,
+                },
+                world: super::WorldDefinition {
+                    states: upgrade_set(self.world.states, StateName::upgrade),
+                },
+                maps: self
+                    .maps
+                    .into_iter()
+                    .map(|(map, map_definition)| (map, map_definition.upgrade()))
+                    .collect(),
+                entity_states: self
+                    .entity_states
+                    .into_iter()
+                    .map(|(entity, states)| {
+                        (entity.upgrade(), upgrade_set(states, StateName::upgrade))
+                    })
+                    .collect(),
+            }
+        }
+    }
+}
@@ -1,18 +1,20 @@
+mod format;
 mod types;
 
 pub use types::{
-    EntityInstanceDefinition, MetaSaveData, PlayerDefinition, Position, QuestDefinition, SaveData,
-    TaskStatus, WorldDefinition,
+    EntityInstanceDefinition, Inventory, ItemStack, MapDefinition, MetaSaveData, PlayerDefinition,
+    Position, QuestDefinition, SaveData, TaskStatus, WorldDefinition,
 };
 
 use super::{
-    ecs,
+    ecs::{self, resources::MapRegistry},
     error::types::GameResult,
     game_state::GameState,
     input,
     utils::{self, time},
 };
 use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
 
 const SAVE_FILE_DIR: &str = "/saves";
 const SAVE_FILE_EXT: &str = "sav";
@@ -54,6 +56,13 @@ fn get_user_data_vfs(ctx: &mut ggez::Context) -> GameResult<&Box<dyn ggez::vfs::
         .ok_or_else(|| ggez::GameError::FilesystemError("Couldn't find user data vfs".to_string()))
 }
 
+fn read_file(vfs: &Box<dyn ggez::vfs::VFS>, path: &std::path::Path) -> GameResult<Vec<u8>> {
+    let mut bytes = vec![];
+    vfs.open(path)?.read_to_end(&mut bytes)?;
+
+    Ok(bytes)
+}
+
 fn save_created_data(
     ctx: &mut ggez::Context,
     slot: SaveSlot,
@@ -88,63 +97,46 @@ fn save_created_data(
     let meta_file_path = saves_path.join(meta_filename);
     let backup_meta_file_path = saves_path.join(backup_meta_filename);
 
+    // Old saves are backed up as they are, and kept for good if this version can't read them
+    let mut keep_backups = false;
+
     if vfs.exists(&save_file_path) {
-        let save_file = vfs.open(&save_file_path)?;
-        let meta_file = vfs.open(&meta_file_path)?;
-
-        let old_save_data: Option<SaveData> = match bincode::deserialize_from(save_file) {
-            Ok(old_save_data) => Some(old_save_data),
-            Err(e) => {
-                println!("Error deserializing old save data: {}", e);
-                None
-            }
-        };
-        let old_meta_data: Option<MetaSaveData> = match bincode::deserialize_from(meta_file) {
-            Ok(old_meta_data) => Some(old_meta_data),
-            Err(e) => {
-                println!("Error deserializing old meta data: {}", e);
-                None
-            }
-        };
-
-        if let Some(old_save_data) = old_save_data {
-            let backup_file = vfs.create(&backup_file_path)?;
-            if let Err(e) = bincode::serialize_into(backup_file, &old_save_data) {
-                println!("Error serializing old save data into backup file: {}", e);
-            }
+        let old_save_bytes = read_file(vfs, &save_file_path)?;
+
+        if let Err(e) = format::read_save_data(save_filename, &old_save_bytes) {
+            println!("Keeping a backup of the old save: {}", e);
+            keep_backups = true;
         }
-        if let Some(old_meta_data) = old_meta_data {
-            let backup_meta_file = vfs.create(&backup_meta_file_path)?;
-            if let Err(e) = bincode::serialize_into(backup_meta_file, &old_meta_data) {
-                println!("Error serializing old meta data into backup file: {}", e);
-            }
+
+        vfs.create(&backup_file_path)?.write_all(&old_save_bytes)?;
+
+        if vfs.exists(&meta_file_path) {
+            let old_meta_bytes = read_file(vfs, &meta_file_path)?;
+
+            vfs.create(&backup_meta_file_path)?.write_all(&old_meta_bytes)?;
         }
     }
 
-    let save_file = vfs.create(&save_file_path)?;
-    let meta_file = vfs.create(&meta_file_path)?;
-
-    bincode::serialize_into(save_file, &save_data).map_err(|e| {
-        ggez::GameError::CustomError(format!(
-            "Error serializing save data into save file: {:?}\n{}",
-            save_data, e
-        ))
-    })?;
-
-    bincode::serialize_into(meta_file, &meta_save_data).map_err(|e| {
-        ggez::GameError::CustomError(format!(
-            "Error serializing meta data into meta file: {:?}\n{}",
-            meta_save_data, e
-        ))
-    })?;
-
-    let save_file = vfs.open(&save_file_path)?;
-    let save_data_check: SaveData = bincode::deserialize_from(save_file).map_err(|e| {
-        ggez::GameError::CustomError(format!(
-            "Failed to read save data: {:?}\n{}",
-            save_file_path, e
-        ))
-    })?;
+    vfs.create(&save_file_path)?
+        .write_all(&format::write_save_data(&save_data)?)
+        .map_err(|e| {
+            ggez::GameError::CustomError(format!(
+                "Error writing save data into save file: {:?}\n{}",
+                save_data, e
+            ))
+        })?;
+
+    vfs.create(&meta_file_path)?
+        .write_all(&format::write_meta_save_data(&meta_save_data)?)
+        .map_err(|e| {
+            ggez::GameError::CustomError(format!(
+                "Error writing meta data into meta file: {:?}\n{}",
+                meta_save_data, e
+            ))
+        })?;
+
+    let save_data_check =
+        format::read_save_data(save_filename, &read_file(vfs, &save_file_path)?)?;
 
     if save_data != save_data_check {
         return Err(ggez::GameError::CustomError(format!(
@@ -154,19 +146,26 @@ fn save_created_data(
     }
 
     // Delete backup save now that main save is confirmed valid
-    if vfs.exists(&backup_file_path) {
-        vfs.rm(&backup_file_path)?;
-    }
-    if vfs.exists(&backup_meta_file_path) {
-        vfs.rm(&backup_meta_file_path)?;
+    if !keep_backups {
+        if vfs.exists(&backup_file_path) {
+            vfs.rm(&backup_file_path)?;
+        }
+        if vfs.exists(&backup_meta_file_path) {
+            vfs.rm(&backup_meta_file_path)?;
+        }
     }
 
     Ok(())
 }
 
-pub fn new_save(ctx: &mut ggez::Context, slot: SaveSlot, name: String) -> GameResult {
-    let save_data = SaveData::new();
-    let meta_data = MetaSaveData::new(name);
+pub fn new_save(
+    ctx: &mut ggez::Context,
+    map_registry: &MapRegistry,
+    slot: SaveSlot,
+    name: String,
+) -> GameResult {
+    let save_data = SaveData::new(map_registry);
+    let meta_data = MetaSaveData::new(name, map_registry.start.map.clone());
 
     save_created_data(ctx, slot, save_data, meta_data)
 }
@@ -188,10 +187,10 @@ pub fn load(game_state: &mut GameState, ctx: &mut ggez::Context, slot: SaveSlot)
         SAVE_FILE_EXT
     ));
 
-    let save_file = vfs.open(&save_file_path)?;
-    let save_data: SaveData = bincode::deserialize_from(save_file).map_err(|e| {
-        ggez::GameError::CustomError(format!("Error deserializing save data: {}", e))
-    })?;
+    let save_data = format::read_save_data(
+        &save_file_path.to_string_lossy(),
+        &read_file(vfs, &save_file_path)?,
+    )?;
 
     save_data.to_game_state(game_state)
 }
@@ -210,10 +209,10 @@ pub fn load_meta(ctx: &mut ggez::Context, slot: SaveSlot) -> GameResult<Option<M
         return Ok(None);
     }
 
-    let meta_file = vfs.open(&meta_file_path)?;
-    let meta_data: MetaSaveData = bincode::deserialize_from(meta_file).map_err(|e| {
-        ggez::GameError::CustomError(format!("Error deserializing meta save data: {}", e))
-    })?;
+    let meta_data = format::read_meta_save_data(
+        &meta_file_path.to_string_lossy(),
+        &read_file(vfs, &meta_file_path)?,
+    )?;
 
     Ok(Some(meta_data))
 }
@@ -303,7 +302,7 @@ impl SaveData {
             #[cfg(debug_assertions)]
             let target_position = target_position as &mut TargetPosition;
 
-            let player_entity = tile_map
+            let player_entity = tile_map.grid
                 .get_tile_mut(target_position.x, target_position.y)
                 .entity
                 .take()
@@ -312,7 +311,7 @@ impl SaveData {
                     target_position.x, target_position.y, current_position, target_position
                 ));
 
-            tile_map.get_tile_mut(position.0, position.1).entity = Some(player_entity);
+            tile_map.grid.get_tile_mut(position.0, position.1).entity = Some(player_entity);
 
             current_position.x = position.0 as f32;
             current_position.y = position.1 as f32;
@@ -1,7 +1,10 @@
 use super::{
-    ecs::components::{
-        ChoiceName, CurrentPosition, EntityName, FacingDirection, MapName, QuestName, StateName,
-        TargetPosition, TaskName,
+    ecs::{
+        components::{
+            ChoiceName, CurrentPosition, EntityName, FacingDirection, ItemId, MapName, QuestName,
+            StateName, TargetPosition, TaskName,
+        },
+        resources::MapRegistry,
     },
     input::types::GameDirection,
     utils, GameResult, GameState,
@@ -23,6 +26,18 @@ pub enum TaskStatus {
 pub struct QuestDefinition {
     pub tasks: HashMap<TaskName, TaskStatus>,
     pub choices: HashMap<ChoiceName, bool>,
+    // Who the player talked to while each task was active
+    pub talked_to: HashMap<TaskName, HashSet<EntityName>>,
+}
+
+impl QuestDefinition {
+    pub fn new() -> Self {
+        Self {
+            tasks: utils::map!(),
+            choices: utils::map!(),
+            talked_to: utils::map!(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -32,11 +47,74 @@ pub struct Position {
     pub facing: Option<GameDirection>,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct ItemStack {
+    pub item: ItemId,
+    pub quantity: usize,
+}
+
+// Each item has a single stack, in the order they were first put in the bag
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
+pub struct Inventory {
+    pub stacks: Vec<ItemStack>,
+}
+
+impl Inventory {
+    pub fn quantity(&self, item: &ItemId) -> usize {
+        self.stacks
+            .iter()
+            .find(|stack| stack.item == *item)
+            .map_or(0, |stack| stack.quantity)
+    }
+
+    pub fn room_for(&self, item: &ItemId, max_stack: usize) -> usize {
+        max_stack.saturating_sub(self.quantity(item))
+    }
+
+    // Returns how many didn't fit
+    pub fn add(&mut self, item: &ItemId, quantity: usize, max_stack: usize) -> usize {
+        let added = quantity.min(self.room_for(item, max_stack));
+
+        if added > 0 {
+            match self.stacks.iter_mut().find(|stack| stack.item == *item) {
+                Some(stack) => stack.quantity += added,
+                None => self.stacks.push(ItemStack {
+                    item: item.clone(),
+                    quantity: added,
+                }),
+            }
+        }
+
+        quantity - added
+    }
+
+    // Nothing is taken unless there's enough of the item
+    pub fn remove(&mut self, item: &ItemId, quantity: usize) -> bool {
+        let idx = match self.stacks.iter().position(|stack| stack.item == *item) {
+            Some(idx) => idx,
+            None => return quantity == 0,
+        };
+
+        if self.stacks[idx].quantity < quantity {
+            return false;
+        }
+
+        self.stacks[idx].quantity -= quantity;
+
+        if self.stacks[idx].quantity == 0 {
+            self.stacks.remove(idx);
+        }
+
+        true
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct PlayerDefinition {
     pub map: MapName,
     pub position: Position,
     pub journal: HashMap<QuestName, QuestDefinition>,
+    pub inventory: Inventory,
 }
 
 impl PlayerDefinition {
@@ -47,8 +125,16 @@ impl PlayerDefinition {
             map,
             position,
             journal,
+            inventory: Inventory::default(),
         }
     }
+
+    pub fn task_status(&self, quest: &QuestName, task: &TaskName) -> TaskStatus {
+        self.journal
+            .get(quest)
+            .and_then(|quest_definition| quest_definition.tasks.get(task).cloned())
+            .unwrap_or(TaskStatus::Unknown)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -64,48 +150,44 @@ impl EntityInstanceDefinition {
         ctx: &mut ggez::Context,
         name: &EntityName,
     ) -> GameResult {
-        match name {
-            EntityName::WiseOldMan => {
-                let (
-                    entity_name_c,
-                    mut target_position_c,
-                    mut current_position_c,
-                    mut facing_direction_c,
-                ): (
-                    specs::ReadStorage<EntityName>,
-                    specs::WriteStorage<TargetPosition>,
-                    specs::WriteStorage<CurrentPosition>,
-                    specs::WriteStorage<FacingDirection>,
-                ) = game_state.world.system_data();
-
-                for (entity_name, target_position, current_position, facing_direction) in (
-                    &entity_name_c,
-                    &mut target_position_c,
-                    &mut current_position_c,
-                    &mut facing_direction_c,
-                )
-                    .join()
-                {
-                    // Help linter
-                    #[cfg(debug_assertions)]
-                    let entity_name = entity_name as &EntityName;
-                    #[cfg(debug_assertions)]
-                    let target_position = target_position as &mut TargetPosition;
-                    #[cfg(debug_assertions)]
-                    let current_position = current_position as &mut CurrentPosition;
-                    #[cfg(debug_assertions)]
-                    let facing_direction = facing_direction as &mut FacingDirection;
-
-                    // Already exists in world
-                    if *name == *entity_name {
-                        return Ok(());
-                    }
-                }
+        let (
+            entity_name_c,
+            mut target_position_c,
+            mut current_position_c,
+            mut facing_direction_c,
+        ): (
+            specs::ReadStorage<EntityName>,
+            specs::WriteStorage<TargetPosition>,
+            specs::WriteStorage<CurrentPosition>,
+            specs::WriteStorage<FacingDirection>,
+        ) = game_state.world.system_data();
 
-                // Needs to be initialized
+        for (entity_name, target_position, current_position, facing_direction) in (
+            &entity_name_c,
+            &mut target_position_c,
+            &mut current_position_c,
+            &mut facing_direction_c,
+        )
+            .join()
+        {
+            // Help linter
+            #[cfg(debug_assertions)]
+            let entity_name = entity_name as &EntityName;
+            #[cfg(debug_assertions)]
+            let target_position = target_position as &mut TargetPosition;
+            #[cfg(debug_assertions)]
+            let current_position = current_position as &mut CurrentPosition;
+            #[cfg(debug_assertions)]
+            let facing_direction = facing_direction as &mut FacingDirection;
+
+            // Already exists in world
+            if *name == *entity_name {
+                return Ok(());
             }
         }
 
+        // Needs to be initialized
+
         Ok(())
     }
 }
@@ -115,6 +197,12 @@ pub struct MapDefinition {
     pub entity_instances: HashMap<EntityName, EntityInstanceDefinition>,
     // pub bulletins: HashMap<BulletinName, BulletinDefinition>,
     pub states: HashSet<StateName>,
+    // Object ids of run-once triggers that have already gone off
+    pub triggered: HashSet<usize>,
+    // Where pushable objects were left, by object id
+    pub pushed: HashMap<usize, (usize, usize)>,
+    // Object ids of pickups that are gone for good
+    pub picked_up: HashSet<usize>,
 }
 
 impl MapDefinition {
@@ -122,6 +210,9 @@ impl MapDefinition {
         Self {
             entity_instances,
             states: utils::set!(),
+            triggered: utils::set!(),
+            pushed: utils::map!(),
+            picked_up: utils::set!(),
         }
     }
 }
@@ -148,36 +239,30 @@ pub struct SaveData {
 }
 
 impl SaveData {
-    pub fn new(/*difficulty: GameDifficulty, */) -> Self {
-        let pallet_town_entity_instances = utils::map!(
-            EntityName::WiseOldMan => EntityInstanceDefinition {
-                position: Position { x: 5, y: 5, facing: Some(GameDirection::Right) },
-                dialog_id: 0,
-            },
-        );
-        let varrock_entity_instances = utils::map!(
-            EntityName::WiseOldMan => EntityInstanceDefinition {
-                position: Position { x: 7, y: 7, facing: Some(GameDirection::Down) },
-                dialog_id: 0,
-            },
-        );
+    pub fn new(map_registry: &MapRegistry /*, difficulty: GameDifficulty */) -> Self {
+        // NPCs are added to their map's entity instances once they first spawn
+        let maps: HashMap<MapName, MapDefinition> = map_registry
+            .maps
+            .keys()
+            .map(|map_name| (map_name.clone(), MapDefinition::new(utils::map!())))
+            .collect();
 
-        let maps = utils::map!(
-            MapName::PalletTown => MapDefinition::new(pallet_town_entity_instances),
-            MapName::Varrock => MapDefinition::new(varrock_entity_instances),
-        );
+        let entity_states = map_registry
+            .maps
+            .values()
+            .flat_map(|manifest| manifest.entities.iter())
+            .map(|entity| (entity.name.clone(), utils::set!()))
+            .collect();
 
-        let entity_states = utils::map!(
-            EntityName::WiseOldMan => utils::set!(),
-        );
+        let start = &map_registry.start;
 
         Self {
             player: PlayerDefinition::new(
-                MapName::Varrock,
+                start.map.clone(),
                 Position {
-                    x: 4,
-                    y: 8,
-                    facing: Some(GameDirection::Down),
+                    x: start.x,
+                    y: start.y,
+                    facing: start.facing,
                 },
             ),
             world: WorldDefinition::new(),
@@ -211,10 +296,10 @@ pub struct MetaSaveData {
 }
 
 impl MetaSaveData {
-    pub fn new(name: String) -> Self {
+    pub fn new(name: String, current_map: MapName) -> Self {
         Self {
             name,
-            current_map: MapName::Varrock,
+            current_map,
             seconds_played: 0,
             finished: false,
         }
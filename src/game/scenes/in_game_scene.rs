@@ -5,7 +5,7 @@ use super::{
     input::types::{GameButton, GameInput},
     save::{self, MetaSaveData, SaveSlot},
     types::{Scene, SceneBuilder, SceneSwitch},
-    world, MainMenuScene, OverworldScene,
+    world, MainMenuScene, OverworldScene, Transition,
 };
 use std::{cell::RefCell, rc::Rc};
 
@@ -97,7 +97,10 @@ impl Scene for InGameScene {
                                 Ok(Rc::new(RefCell::new(scene)))
                             });
 
-                            return Ok(Some(SceneSwitch::ReplaceAll(scene_builder)));
+                            return Ok(Some(SceneSwitch::Transition(
+                                Transition::fade_to_black(),
+                                Box::new(SceneSwitch::ReplaceAll(scene_builder)),
+                            )));
                         }
                         _ => {}
                     }
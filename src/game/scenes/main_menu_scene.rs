@@ -1,12 +1,13 @@
 use super::{
     config,
+    ecs::resources::MapRegistry,
     error::types::GameResult,
     game_state::GameState,
     input::types::{GameButton, GameInput},
     save::{self, MetaSaveData, SaveSlot},
     settings,
     types::{Scene, SceneBuilder, SceneSwitch},
-    InGameScene,
+    InGameScene, Transition,
 };
 use ggez::graphics::Drawable as GgezDrawable;
 use std::{cell::RefCell, rc::Rc};
@@ -44,7 +45,11 @@ impl MainMenuScene {
 
         let mut saves = vec![];
         for save_slot in SaveSlot::all() {
-            saves.push(save::load_meta(ctx, save_slot)?);
+            // Slots this version can't read start a new save, which backs up the old one first
+            saves.push(save::load_meta(ctx, save_slot).unwrap_or_else(|e| {
+                println!("Save slot {} can't be loaded: {}", save_slot.id(), e);
+                None
+            }));
         }
 
         Ok(Self {
@@ -113,6 +118,9 @@ impl Scene for MainMenuScene {
                                                     None => {
                                                         save::new_save(
                                                             ctx,
+                                                            &game_state
+                                                                .world
+                                                                .fetch::<MapRegistry>(),
                                                             save_slot,
                                                             format!("Adam{}", save_slot.id()),
                                                         )?;
@@ -130,7 +138,10 @@ impl Scene for MainMenuScene {
                                             },
                                         );
 
-                                        return Ok(Some(SceneSwitch::ReplaceAll(scene_builder)));
+                                        return Ok(Some(SceneSwitch::Transition(
+                                            Transition::fade_from_black(),
+                                            Box::new(SceneSwitch::ReplaceAll(scene_builder)),
+                                        )));
                                     }
                                     _ => println!("Invalid save slot: {}", selected_save),
                                 }
@@ -1,13 +1,15 @@
 mod in_game_scene;
 mod main_menu_scene;
 mod overworld;
+mod transition;
 
 pub mod types;
 
 pub use in_game_scene::InGameScene;
 pub use main_menu_scene::MainMenuScene;
 pub use overworld::{
-    OverworldScene, PalletTownOverworldScene, PauseMenuScene, TextBoxScene, VarrockOverworldScene,
+    BagScene, CutsceneScene, MapScene, OverworldScene, PauseMenuScene, TextBoxScene,
 };
+pub use transition::Transition;
 
-use super::{config, ecs, error, game_state, input, maps, save, settings, utils, world};
+use super::{config, ecs, error, game_state, input, maps, save, scripting, settings, world};
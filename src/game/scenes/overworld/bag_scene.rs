@@ -0,0 +1,255 @@
+use super::{
+    ecs::{
+        components::ItemId,
+        resources::{CutsceneRequest, ItemCategory, ItemRegistry},
+    },
+    error::types::GameResult,
+    game_state::GameState,
+    input::types::{GameButton, GameInput},
+    save::SaveData,
+    scripting::ScriptEngine,
+    types::{Scene, SceneSwitch},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BagAction {
+    Use,
+    Toss,
+}
+
+pub struct BagScene {
+    category: usize,
+    selected: usize,
+    // Picking what to do with the selected item
+    action: Option<BagAction>,
+}
+
+impl BagScene {
+    pub fn new(game_state: &GameState) -> Self {
+        let scene = BagScene {
+            category: 0,
+            selected: 0,
+            action: None,
+        };
+
+        scene.print(game_state);
+
+        scene
+    }
+
+    fn category(&self) -> ItemCategory {
+        ItemCategory::ALL[self.category]
+    }
+
+    // Items the registry doesn't know about aren't in any pocket
+    fn items(&self, game_state: &GameState) -> Vec<(ItemId, usize)> {
+        let item_registry = game_state.world.fetch::<ItemRegistry>();
+        let save_data = game_state.world.fetch::<SaveData>();
+
+        save_data
+            .player
+            .inventory
+            .stacks
+            .iter()
+            .filter(|stack| {
+                item_registry
+                    .items
+                    .get(&stack.item)
+                    .map_or(false, |item_definition| {
+                        item_definition.category == self.category()
+                    })
+            })
+            .map(|stack| (stack.item.clone(), stack.quantity))
+            .collect()
+    }
+
+    // TODO: Presentation, this only prints the bag for now
+    fn print(&self, game_state: &GameState) {
+        let item_registry = game_state.world.fetch::<ItemRegistry>();
+
+        println!("Bag: {:?}", self.category());
+
+        for (idx, (item, quantity)) in self.items(game_state).iter().enumerate() {
+            let cursor = if idx == self.selected { ">" } else { " " };
+
+            if let Ok(item_definition) = item_registry.get(item) {
+                println!(
+                    "{} {} x{} - {}",
+                    cursor, item_definition.name, quantity, item_definition.description
+                );
+            }
+        }
+
+        if let Some(action) = self.action {
+            println!("{:?}?", action);
+        }
+    }
+
+    fn move_selection(&mut self, game_state: &GameState, up: bool) {
+        let len = self.items(game_state).len();
+
+        if len == 0 {
+            return;
+        }
+
+        self.selected = if up {
+            (self.selected + len - 1) % len
+        } else {
+            (self.selected + 1) % len
+        };
+    }
+
+    fn change_category(&mut self, right: bool) {
+        let len = ItemCategory::ALL.len();
+
+        self.category = if right {
+            (self.category + 1) % len
+        } else {
+            (self.category + len - 1) % len
+        };
+        self.selected = 0;
+    }
+
+    // Using an item closes the bag so whatever its script does can play out
+    fn use_item(
+        &mut self,
+        game_state: &mut GameState,
+        ctx: &mut ggez::Context,
+        item: &ItemId,
+    ) -> GameResult<Option<SceneSwitch>> {
+        let item_definition = game_state.world.fetch::<ItemRegistry>().get(item)?.clone();
+
+        let script = match item_definition.script {
+            Some(script) => script,
+            None => {
+                println!("{} can't be used here", item_definition.name);
+                return Ok(None);
+            }
+        };
+
+        let commands = ScriptEngine::run(&game_state.world, ctx, &script, "on_use");
+
+        if !commands.is_empty() {
+            game_state
+                .world
+                .fetch_mut::<CutsceneRequest>()
+                .requesting
+                .get_or_insert_with(Vec::new)
+                .extend(commands);
+        }
+
+        Ok(Some(SceneSwitch::Pop))
+    }
+
+    // One at a time, and key items can't be tossed at all
+    fn toss_item(&mut self, game_state: &mut GameState, item: &ItemId) -> GameResult {
+        let item_definition = game_state.world.fetch::<ItemRegistry>().get(item)?.clone();
+
+        if item_definition.category == ItemCategory::Key {
+            println!("{} is too important to toss", item_definition.name);
+            return Ok(());
+        }
+
+        game_state
+            .world
+            .fetch_mut::<SaveData>()
+            .player
+            .inventory
+            .remove(item, 1);
+
+        let len = self.items(game_state).len();
+        if self.selected >= len {
+            self.selected = len.saturating_sub(1);
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for BagScene {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!("{} {{ ... }}", self.name()))
+    }
+}
+
+impl Scene for BagScene {
+    fn dispose(&mut self, _game_state: &mut GameState, _ctx: &mut ggez::Context) -> GameResult {
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        _game_state: &mut GameState,
+        _ctx: &mut ggez::Context,
+        _delta_secs: f32,
+    ) -> GameResult<Option<SceneSwitch>> {
+        Ok(None)
+    }
+
+    fn draw(&self, _game_state: &GameState, _ctx: &mut ggez::Context) -> GameResult {
+        Ok(())
+    }
+
+    fn input(
+        &mut self,
+        game_state: &mut GameState,
+        ctx: &mut ggez::Context,
+        input: GameInput,
+    ) -> GameResult<Option<SceneSwitch>> {
+        let button = match input {
+            GameInput::Button {
+                button,
+                pressed: true,
+            } => button,
+            _ => return Ok(None),
+        };
+
+        match (self.action, button) {
+            (None, GameButton::Up) => self.move_selection(game_state, true),
+            (None, GameButton::Down) => self.move_selection(game_state, false),
+            (None, GameButton::Left) => self.change_category(false),
+            (None, GameButton::Right) => self.change_category(true),
+            (None, GameButton::Primary) => {
+                if !self.items(game_state).is_empty() {
+                    self.action = Some(BagAction::Use);
+                }
+            }
+            (None, GameButton::Start) | (None, GameButton::Secondary) => {
+                return Ok(Some(SceneSwitch::Pop))
+            }
+            (Some(action), GameButton::Up) | (Some(action), GameButton::Down) => {
+                self.action = Some(match action {
+                    BagAction::Use => BagAction::Toss,
+                    BagAction::Toss => BagAction::Use,
+                });
+            }
+            (Some(action), GameButton::Primary) => {
+                self.action = None;
+
+                let item = match self.items(game_state).get(self.selected) {
+                    Some((item, _)) => item.clone(),
+                    None => return Ok(None),
+                };
+
+                match action {
+                    BagAction::Use => return self.use_item(game_state, ctx, &item),
+                    BagAction::Toss => self.toss_item(game_state, &item)?,
+                }
+            }
+            (Some(_), GameButton::Secondary) => self.action = None,
+            _ => return Ok(None),
+        }
+
+        self.print(game_state);
+
+        Ok(None)
+    }
+
+    fn should_draw_previous(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &str {
+        "BagScene"
+    }
+}
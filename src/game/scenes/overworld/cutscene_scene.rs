@@ -0,0 +1,471 @@
+use super::{
+    ecs::{
+        components::{
+            EntityName, FacingDirection, FacingPlayer, Footprint, Player, Scripted, SpriteSheet,
+            TargetPosition, Timer,
+        },
+        resources::{
+            Camera, CutsceneCommand, CutsceneRequest, CutsceneTarget, ItemRegistry, MapWarp,
+            MovementMode, PlayerMovementRequest, QuestRegistry, QuestUpdates,
+            ShouldUpdateBackgroundTiles, TileMap, WarpRequest,
+        },
+    },
+    error::types::GameResult,
+    game_state::GameState,
+    input::types::{GameDirection, GameInput},
+    save::SaveData,
+    types::{Scene, SceneBuilder, SceneSwitch},
+    TextBoxScene,
+};
+use specs::{Entity, Join, WorldExt};
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+};
+
+// A command that takes time, waited on before the next one starts
+enum RunningCommand {
+    Move {
+        entity: Entity,
+        direction: GameDirection,
+        tiles_left: usize,
+    },
+    ShowText {
+        closed: Rc<Cell<bool>>,
+    },
+    Wait {
+        timer: Timer,
+    },
+    PanCamera {
+        from: (f32, f32),
+        to: (f32, f32),
+        timer: Timer,
+    },
+}
+
+pub struct CutsceneScene {
+    commands: VecDeque<CutsceneCommand>,
+    running: Option<RunningCommand>,
+}
+
+fn find_entity(world: &specs::World, target: &CutsceneTarget) -> Option<Entity> {
+    let entities = world.entities();
+
+    match target {
+        CutsceneTarget::Player => (&entities, &world.read_storage::<Player>())
+            .join()
+            .map(|(entity, _)| entity)
+            .next(),
+        CutsceneTarget::Entity(name) => (&entities, &world.read_storage::<EntityName>())
+            .join()
+            .find(|(_, entity_name)| *entity_name == name)
+            .map(|(entity, _)| entity),
+    }
+}
+
+// Holds the entity still for the rest of the cutscene, other than what the cutscene does with it
+fn script_entity(world: &specs::World, target: &CutsceneTarget) -> GameResult<Option<Entity>> {
+    let entity = match find_entity(world, target) {
+        Some(entity) => entity,
+        None => {
+            log::warn!("Skipping cutscene command, {:?} isn't in the map", target);
+            return Ok(None);
+        }
+    };
+
+    world
+        .write_storage::<Scripted>()
+        .insert(entity, Scripted)
+        .map_err(|e| {
+            ggez::GameError::CustomError(format!(
+                "Couldn't script {:?} for a cutscene: {}",
+                target, e
+            ))
+        })?;
+
+    Ok(Some(entity))
+}
+
+// Starts walking a tile the same way the player and NPCs do, unless something is in the way
+fn start_step(world: &specs::World, entity: Entity, direction: GameDirection) -> bool {
+    let (
+        mut tile_map_r,
+        mut should_update_background_tiles_r,
+        mut save_data_r,
+        player_c,
+        entity_name_c,
+        footprint_c,
+        mut target_position_c,
+        mut timer_c,
+        mut sprite_sheet_c,
+        mut facing_direction_c,
+    ): (
+        Option<specs::Write<TileMap>>,
+        specs::Write<ShouldUpdateBackgroundTiles>,
+        Option<specs::Write<SaveData>>,
+        specs::ReadStorage<Player>,
+        specs::ReadStorage<EntityName>,
+        specs::ReadStorage<Footprint>,
+        specs::WriteStorage<TargetPosition>,
+        specs::WriteStorage<Timer>,
+        specs::WriteStorage<SpriteSheet>,
+        specs::WriteStorage<FacingDirection>,
+    ) = world.system_data();
+
+    let tile_map = match &mut tile_map_r {
+        Some(tile_map) => tile_map,
+        None => return false,
+    };
+
+    let (target_position, timer) =
+        match (target_position_c.get_mut(entity), timer_c.get_mut(entity)) {
+            (Some(target_position), Some(timer)) => (target_position, timer),
+            _ => return false,
+        };
+
+    if let Some(facing_direction) = facing_direction_c.get_mut(entity) {
+        facing_direction.direction = direction;
+    }
+
+    let footprint = footprint_c.get(entity).cloned().unwrap_or_default();
+    let (direction_x, direction_y) = direction.to_xy();
+
+    let x = target_position.x as isize + direction_x;
+    let y = target_position.y as isize + direction_y;
+
+    if !tile_map.grid.has_room_for(entity, x, y, &footprint) {
+        return false;
+    }
+
+    let (x, y) = (x as usize, y as usize);
+
+    tile_map.grid.move_entity(
+        entity,
+        (target_position.x, target_position.y),
+        (x, y),
+        &footprint,
+    );
+
+    timer.set_duration(MovementMode::Walk.seconds_per_tile());
+    timer.reset();
+    timer.set_should_tick(true);
+
+    should_update_background_tiles_r.0 = true;
+
+    target_position.x = x;
+    target_position.y = y;
+    target_position.is_moving = true;
+
+    if let Some(sprite_sheet) = sprite_sheet_c.get_mut(entity) {
+        sprite_sheet.set_row(MovementMode::Walk.sprite_sheet_row(&direction));
+    }
+
+    if let Some(save_data) = &mut save_data_r {
+        if player_c.get(entity).is_some() {
+            save_data.player.position.x = x;
+            save_data.player.position.y = y;
+            save_data.player.position.facing = Some(direction);
+        } else if let Some(entity_name) = entity_name_c.get(entity) {
+            let map = save_data.player.map.clone();
+
+            if let Some(entity_instance) = save_data
+                .maps
+                .get_mut(&map)
+                .and_then(|map_definition| map_definition.entity_instances.get_mut(entity_name))
+            {
+                entity_instance.position.x = x;
+                entity_instance.position.y = y;
+                entity_instance.position.facing = Some(direction);
+            }
+        }
+    }
+
+    true
+}
+
+impl CutsceneScene {
+    pub fn new(game_state: &mut GameState, commands: Vec<CutsceneCommand>) -> Self {
+        // The player can't move themselves until the cutscene is over
+        let mut player_movement_request = game_state.world.fetch_mut::<PlayerMovementRequest>();
+        player_movement_request.last_requested_direction = None;
+        player_movement_request.last_requested_x_direction = None;
+        player_movement_request.last_requested_y_direction = None;
+        player_movement_request.movement_mode = MovementMode::Walk;
+
+        Self {
+            commands: commands.into(),
+            running: None,
+        }
+    }
+
+    fn start(
+        &mut self,
+        game_state: &mut GameState,
+        command: CutsceneCommand,
+    ) -> GameResult<Option<SceneSwitch>> {
+        let world = &mut game_state.world;
+
+        match command {
+            CutsceneCommand::Move {
+                target,
+                direction,
+                tiles,
+            } => {
+                if let Some(entity) = script_entity(world, &target)? {
+                    self.running = Some(RunningCommand::Move {
+                        entity,
+                        direction,
+                        tiles_left: tiles,
+                    });
+                }
+            }
+            CutsceneCommand::Face { target, direction } => {
+                if let Some(entity) = script_entity(world, &target)? {
+                    FacingDirection::turn(world, entity, direction);
+                }
+            }
+            CutsceneCommand::ShowText { text } => {
+                let closed = Rc::new(Cell::new(false));
+                self.running = Some(RunningCommand::ShowText {
+                    closed: Rc::clone(&closed),
+                });
+
+                let scene_builder: SceneBuilder = Box::new(move |_, _| {
+                    let scene = TextBoxScene::from_text(&text, Rc::clone(&closed));
+                    Ok(Rc::new(RefCell::new(scene)))
+                });
+
+                return Ok(Some(SceneSwitch::Push(scene_builder)));
+            }
+            CutsceneCommand::Wait { seconds } => {
+                self.running = Some(RunningCommand::Wait {
+                    timer: Timer::from_seconds(seconds, false),
+                });
+            }
+            CutsceneCommand::SetState { state, on } => {
+                let mut save_data = world.fetch_mut::<SaveData>();
+
+                if on {
+                    save_data.world.states.insert(state);
+                } else {
+                    save_data.world.states.remove(&state);
+                }
+            }
+            CutsceneCommand::SetTask {
+                quest,
+                task,
+                status,
+            } => {
+                let update = world.fetch::<QuestRegistry>().set_task(
+                    &mut world.fetch_mut::<SaveData>(),
+                    &quest,
+                    &task,
+                    status,
+                );
+
+                match update {
+                    Ok(Some(update)) => world.fetch_mut::<QuestUpdates>().updates.push(update),
+                    Ok(None) => {}
+                    Err(e) => log::warn!("{}", e),
+                }
+            }
+            CutsceneCommand::GiveItem { item, quantity } => {
+                let max_stack = match world.fetch::<ItemRegistry>().get(&item) {
+                    Ok(item_definition) => item_definition.max_stack,
+                    Err(e) => {
+                        log::error!("{}", e);
+                        return Ok(None);
+                    }
+                };
+
+                let lost = world
+                    .fetch_mut::<SaveData>()
+                    .player
+                    .inventory
+                    .add(&item, quantity, max_stack);
+
+                if lost > 0 {
+                    log::warn!("No room in the bag for {} more {}", lost, item);
+                }
+            }
+            CutsceneCommand::TakeItem { item, quantity } => {
+                let inventory = &mut world.fetch_mut::<SaveData>().player.inventory;
+
+                if !inventory.remove(&item, quantity) {
+                    log::warn!("Couldn't take {} {} from the bag", quantity, item);
+                }
+            }
+            CutsceneCommand::PanCamera { x, y, seconds } => {
+                let camera = world.fetch::<Camera>();
+
+                self.running = Some(RunningCommand::PanCamera {
+                    from: (camera.pan_x, camera.pan_y),
+                    to: (x, y),
+                    timer: Timer::from_seconds(seconds, false),
+                });
+            }
+            CutsceneCommand::Warp { map, x, y, facing } => {
+                // The overworld loads the map once this is gone, and then starts the rest of the
+                // cutscene over again in there
+                world.fetch_mut::<WarpRequest>().requesting = Some(MapWarp {
+                    to_map: map,
+                    x,
+                    y,
+                    facing,
+                });
+
+                if !self.commands.is_empty() {
+                    world.fetch_mut::<CutsceneRequest>().requesting =
+                        Some(self.commands.drain(..).collect());
+                }
+
+                return Ok(Some(SceneSwitch::Pop));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl RunningCommand {
+    fn update(&mut self, world: &specs::World, delta_secs: f32) -> bool {
+        match self {
+            Self::Move {
+                entity,
+                direction,
+                tiles_left,
+            } => {
+                let is_stepping = world
+                    .read_storage::<Timer>()
+                    .get(*entity)
+                    .map_or(false, |timer| timer.should_tick());
+
+                if is_stepping {
+                    return false;
+                }
+
+                if *tiles_left == 0 {
+                    FacingDirection::turn(world, *entity, *direction);
+                    return true;
+                }
+
+                if !start_step(world, *entity, *direction) {
+                    log::warn!(
+                        "Cutscene move stopped short by {} tiles, something is in the way",
+                        tiles_left
+                    );
+
+                    FacingDirection::turn(world, *entity, *direction);
+                    return true;
+                }
+
+                *tiles_left -= 1;
+                false
+            }
+            Self::ShowText { closed } => closed.get(),
+            Self::Wait { timer } => {
+                timer.tick(delta_secs);
+                timer.finished()
+            }
+            Self::PanCamera { from, to, timer } => {
+                timer.tick(delta_secs);
+
+                let percent = timer.percent();
+                let mut camera = world.fetch_mut::<Camera>();
+                camera.pan_x = from.0 + (to.0 - from.0) * percent;
+                camera.pan_y = from.1 + (to.1 - from.1) * percent;
+
+                timer.finished()
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for CutsceneScene {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!(
+            "{} {{ commands_left: {}, ... }}",
+            self.name(),
+            self.commands.len()
+        ))
+    }
+}
+
+impl Scene for CutsceneScene {
+    fn dispose(&mut self, game_state: &mut GameState, _ctx: &mut ggez::Context) -> GameResult {
+        game_state.world.write_storage::<Scripted>().clear();
+
+        // Scripted interactions run as cutscenes, so whoever was talked to is let go here
+        let facing_player_entities: Vec<Entity> = (
+            &game_state.world.entities(),
+            &game_state.world.read_storage::<FacingPlayer>(),
+        )
+            .join()
+            .map(|(entity, _)| entity)
+            .collect();
+
+        for entity in facing_player_entities {
+            FacingPlayer::stop(&mut game_state.world, entity);
+        }
+
+        // Cutscenes that don't pan back themselves shouldn't leave the camera off somewhere else
+        let mut camera = game_state.world.fetch_mut::<Camera>();
+        camera.pan_x = 0.;
+        camera.pan_y = 0.;
+
+        Ok(())
+    }
+
+    #[tracing::instrument]
+    fn update(
+        &mut self,
+        game_state: &mut GameState,
+        _ctx: &mut ggez::Context,
+        delta_secs: f32,
+    ) -> GameResult<Option<SceneSwitch>> {
+        loop {
+            if let Some(running) = &mut self.running {
+                if !running.update(&game_state.world, delta_secs) {
+                    return Ok(None);
+                }
+
+                self.running = None;
+            }
+
+            let command = match self.commands.pop_front() {
+                Some(command) => command,
+                None => return Ok(Some(SceneSwitch::Pop)),
+            };
+
+            if let Some(scene_switch) = self.start(game_state, command)? {
+                return Ok(Some(scene_switch));
+            }
+        }
+    }
+
+    fn draw(&self, _game_state: &GameState, _ctx: &mut ggez::Context) -> GameResult {
+        Ok(())
+    }
+
+    fn input(
+        &mut self,
+        _game_state: &mut GameState,
+        _ctx: &mut ggez::Context,
+        _input: GameInput,
+    ) -> GameResult<Option<SceneSwitch>> {
+        Ok(None)
+    }
+
+    fn should_update_previous(&self) -> bool {
+        true
+    }
+
+    fn should_draw_previous(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &str {
+        "CutsceneScene"
+    }
+}
@@ -0,0 +1,105 @@
+use super::{
+    ecs::resources::QuestRegistry,
+    error::types::GameResult,
+    game_state::GameState,
+    input::types::{GameButton, GameInput},
+    save::{SaveData, TaskStatus},
+    types::{Scene, SceneSwitch},
+};
+
+pub struct JournalScene;
+
+impl JournalScene {
+    pub fn new(game_state: &GameState) -> Self {
+        let scene = JournalScene;
+
+        scene.print(game_state);
+
+        scene
+    }
+
+    // TODO: Presentation, this only prints the journal for now
+    fn print(&self, game_state: &GameState) {
+        let quest_registry = game_state.world.fetch::<QuestRegistry>();
+        let save_data = game_state.world.fetch::<SaveData>();
+
+        println!("Journal");
+
+        // Tasks the player hasn't found out about yet stay hidden, along with quests that only
+        // have those
+        for quest_info in &quest_registry.quests {
+            let tasks: Vec<_> = quest_info
+                .tasks
+                .iter()
+                .filter_map(|task_info| {
+                    match save_data.player.task_status(&quest_info.id, &task_info.id) {
+                        TaskStatus::Unknown | TaskStatus::NotStarted => None,
+                        status => Some((task_info, status)),
+                    }
+                })
+                .collect();
+
+            if tasks.is_empty() {
+                continue;
+            }
+
+            println!("{}", quest_info.name);
+
+            for (task_info, status) in tasks {
+                println!("  [{:?}] {}", status, task_info.description);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for JournalScene {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!("{} {{ ... }}", self.name()))
+    }
+}
+
+impl Scene for JournalScene {
+    fn dispose(&mut self, _game_state: &mut GameState, _ctx: &mut ggez::Context) -> GameResult {
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        _game_state: &mut GameState,
+        _ctx: &mut ggez::Context,
+        _delta_secs: f32,
+    ) -> GameResult<Option<SceneSwitch>> {
+        Ok(None)
+    }
+
+    fn draw(&self, _game_state: &GameState, _ctx: &mut ggez::Context) -> GameResult {
+        Ok(())
+    }
+
+    fn input(
+        &mut self,
+        _game_state: &mut GameState,
+        _ctx: &mut ggez::Context,
+        input: GameInput,
+    ) -> GameResult<Option<SceneSwitch>> {
+        match input {
+            GameInput::Button {
+                button: GameButton::Start,
+                pressed: true,
+            }
+            | GameInput::Button {
+                button: GameButton::Secondary,
+                pressed: true,
+            } => Ok(Some(SceneSwitch::Pop)),
+            _ => Ok(None),
+        }
+    }
+
+    fn should_draw_previous(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &str {
+        "JournalScene"
+    }
+}
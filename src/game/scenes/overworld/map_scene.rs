@@ -0,0 +1,151 @@
+use super::{
+    ecs::{
+        components::MapName,
+        resources::{
+            CutsceneRequest, DoorRequest, MapRegistry, MovementMode, PlayerMovementRequest,
+            ShouldUpdateBackgroundTiles,
+        },
+    },
+    error::types::GameResult,
+    game_state::GameState,
+    input::types::{GameDirection, GameInput},
+    maps,
+    save::SaveData,
+    scripting::ScriptEngine,
+    types::{Scene, SceneSwitch},
+};
+use specs::Entity;
+use std::collections::HashMap;
+
+// One scene for every map, which loads whatever the map registry has for the map name
+pub struct MapScene {
+    map_name: MapName,
+    scene_entities: Vec<Entity>,
+}
+
+impl MapScene {
+    pub fn new(
+        game_state: &mut GameState,
+        ctx: &mut ggez::Context,
+        map_name: MapName,
+    ) -> GameResult<Self> {
+        {
+            let mut door_request = game_state.world.fetch_mut::<DoorRequest>();
+            door_request.requesting.take();
+        }
+        {
+            let mut player_movement_request = game_state.world.fetch_mut::<PlayerMovementRequest>();
+            player_movement_request.last_requested_direction = None;
+            player_movement_request.last_requested_x_direction = None;
+            player_movement_request.last_requested_y_direction = None;
+            player_movement_request.movement_mode = MovementMode::Walk;
+            game_state.input_state.reset();
+        }
+        {
+            let mut should_update_background_tiles =
+                game_state.world.fetch_mut::<ShouldUpdateBackgroundTiles>();
+            should_update_background_tiles.0 = true;
+        }
+
+        let save_data = {
+            let save_data_r = game_state.world.try_fetch::<SaveData>().ok_or_else(|| {
+                ggez::GameError::CustomError("SaveData resource not found".to_string())
+            })?;
+
+            (*save_data_r).clone()
+        };
+
+        let mut entities = HashMap::new();
+
+        let player_position = (save_data.player.position.x, save_data.player.position.y);
+        let player_direction = save_data
+            .player
+            .position
+            .facing
+            .unwrap_or_else(|| GameDirection::Down);
+        let player_entity =
+            maps::find_and_move_player(game_state, player_position, player_direction)?;
+        entities.insert(player_position, player_entity);
+
+        let scene_entities = maps::load_map(game_state, ctx, &map_name, &mut entities)?;
+
+        let script = game_state
+            .world
+            .fetch::<MapRegistry>()
+            .get(&map_name)?
+            .script
+            .clone();
+
+        if let Some(script) = script {
+            let mut commands = ScriptEngine::run(&game_state.world, ctx, &script, "on_enter");
+
+            if !commands.is_empty() {
+                // A cutscene that warped here carries on after the map's own
+                let mut cutscene_request = game_state.world.fetch_mut::<CutsceneRequest>();
+                commands.extend(cutscene_request.requesting.take().unwrap_or_default());
+                cutscene_request.requesting = Some(commands);
+            }
+        }
+
+        Ok(Self {
+            map_name,
+            scene_entities,
+        })
+    }
+}
+
+impl std::fmt::Debug for MapScene {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!(
+            "{} {{ map_name: {}, ... }}",
+            self.name(),
+            self.map_name
+        ))
+    }
+}
+
+impl Scene for MapScene {
+    fn dispose(&mut self, game_state: &mut GameState, _ctx: &mut ggez::Context) -> GameResult {
+        maps::dispose_map(game_state, self.scene_entities.as_slice())
+    }
+
+    #[tracing::instrument]
+    fn update(
+        &mut self,
+        _game_state: &mut GameState,
+        _ctx: &mut ggez::Context,
+        _delta_secs: f32,
+    ) -> GameResult<Option<SceneSwitch>> {
+        Ok(None)
+    }
+
+    #[tracing::instrument]
+    fn draw(&self, game_state: &GameState, ctx: &mut ggez::Context) -> GameResult {
+        Ok(())
+    }
+
+    fn input(
+        &mut self,
+        _game_state: &mut GameState,
+        _ctx: &mut ggez::Context,
+        _input: GameInput,
+    ) -> GameResult<Option<SceneSwitch>> {
+        Ok(None)
+    }
+
+    fn should_input_previous(&self) -> bool {
+        true
+    }
+
+    fn should_update_previous(&self) -> bool {
+        true
+    }
+
+    fn should_draw_previous(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &str {
+        "MapScene"
+    }
+}
@@ -1,11 +1,17 @@
-mod overworld_maps;
+mod bag_scene;
+mod cutscene_scene;
+mod journal_scene;
+mod map_scene;
 mod overworld_scene;
 mod pause_menu_scene;
 mod text_box_scene;
 
-use super::{config, ecs, error, game_state, input, maps, save, types, utils};
+use super::{config, ecs, error, game_state, input, maps, save, scripting, types, Transition};
 
-pub use overworld_maps::{PalletTownOverworldScene, VarrockOverworldScene};
+pub use bag_scene::BagScene;
+pub use cutscene_scene::CutsceneScene;
+pub use journal_scene::JournalScene;
+pub use map_scene::MapScene;
 pub use overworld_scene::OverworldScene;
 pub use pause_menu_scene::PauseMenuScene;
 pub use text_box_scene::TextBoxScene;
@@ -1,7 +0,0 @@
-mod pallet_town_overworld_scene;
-mod varrock_overworld_scene;
-
-use super::{config, ecs, error, game_state, input, maps, save, types, TextBoxScene};
-
-pub use pallet_town_overworld_scene::PalletTownOverworldScene;
-pub use varrock_overworld_scene::VarrockOverworldScene;
@@ -2,25 +2,31 @@ use super::{
     config,
     ecs::{
         components::{
-            CurrentPosition, Door, Drawable, FacingDirection, Id, Interactable, Player, SpriteRow,
-            SpriteSheet, TargetPosition, Timer,
+            CurrentPosition, Door, Drawable, EntityName, FacingDirection, FacingPlayer, Footprint,
+            Gate, Id, ImageFile, Interactable, NpcMovement, Player, PressurePlate, Pushable,
+            Scripted, SpriteRow, SpriteSheet, TargetPosition, Timer, Trigger,
         },
         resources::{
-            Camera, DoorRequest, PlayerMovementRequest, ShouldUpdateBackgroundTiles, TileMap,
+            Camera, ConnectionRequest, CutsceneRequest, DoorRequest, MapRegistry, MovementMode,
+            PlayerMovementRequest, QuestRegistry, QuestUpdates, Rng, ShouldUpdateBackgroundTiles,
+            TileMap, TileMapLayer, TriggerRequest, WarpRequest,
         },
         systems::{
-            AnimateSystem, FillTileMapToDrawSystem, FollowPlayerSystem,
-            MoveBackgroundDrawParamSystem, MoveCurrentPositionSystem,
-            MovePlayerTargetPositionSystem, UpdateBackgroundTilesSystem, UpdateDrawParamSystem,
+            AdvanceQuestsSystem, AnimateSystem, FillTileMapToDrawSystem, FireTriggersSystem,
+            FollowPlayerSystem, MoveBackgroundDrawParamSystem, MoveCurrentPositionSystem,
+            MoveNpcTargetPositionSystem, MovePlayerTargetPositionSystem, OpenGatesSystem,
+            PressPlatesSystem, UpdateBackgroundTilesSystem, UpdateDrawParamSystem,
             UpdateSpriteSheetDrawParamSystem,
         },
     },
     error::types::GameResult,
     game_state::GameState,
     input::types::{GameButton, GameDirection, GameInput},
-    save::{MetaSaveData, SaveData},
+    maps::DrawOrder,
+    save::{MetaSaveData, SaveData, TaskStatus},
+    scripting::ScriptEngine,
     types::{Scene, SceneBuilder, SceneSwitch},
-    PauseMenuScene,
+    CutsceneScene, PauseMenuScene, Transition,
 };
 use ggez::graphics::Drawable as GgezDrawable;
 use specs::{Builder, Join, WorldExt};
@@ -46,9 +52,10 @@ impl OverworldScene {
         let player_target_position = TargetPosition {
             x: save_data.player.position.x,
             y: save_data.player.position.y,
-            from_x: save_data.player.position.x,
-            from_y: save_data.player.position.y,
+            from_x: save_data.player.position.x as isize,
+            from_y: save_data.player.position.y as isize,
             is_moving: false,
+            is_hopping: false,
         };
         let player_current_position = CurrentPosition {
             x: player_target_position.x as f32,
@@ -68,10 +75,20 @@ impl OverworldScene {
         game_state.world.register::<TargetPosition>();
         game_state.world.register::<Timer>();
         game_state.world.register::<Drawable>();
+        game_state.world.register::<ImageFile>();
         game_state.world.register::<FacingDirection>();
         game_state.world.register::<SpriteSheet>();
         game_state.world.register::<Interactable>();
         game_state.world.register::<Door>();
+        game_state.world.register::<EntityName>();
+        game_state.world.register::<NpcMovement>();
+        game_state.world.register::<Footprint>();
+        game_state.world.register::<FacingPlayer>();
+        game_state.world.register::<Scripted>();
+        game_state.world.register::<Trigger>();
+        game_state.world.register::<Pushable>();
+        game_state.world.register::<PressurePlate>();
+        game_state.world.register::<Gate>();
         game_state.world.insert(PlayerMovementRequest::default());
         game_state.world.insert(Camera {
             x: player_target_position.x as f32,
@@ -80,6 +97,13 @@ impl OverworldScene {
         });
         game_state.world.insert(ShouldUpdateBackgroundTiles(true));
         game_state.world.insert(DoorRequest::default());
+        game_state.world.insert(ConnectionRequest::default());
+        game_state.world.insert(WarpRequest::default());
+        game_state.world.insert(CutsceneRequest::default());
+        game_state.world.insert(TriggerRequest::default());
+        game_state.world.insert(QuestUpdates::default());
+        game_state.world.insert(Rng::default());
+        game_state.world.insert(ScriptEngine::new());
 
         let dispatcher = specs::DispatcherBuilder::new()
             .with(
@@ -87,16 +111,44 @@ impl OverworldScene {
                 "move_player_target_position_system",
                 &[],
             )
+            .with(
+                MoveNpcTargetPositionSystem,
+                "move_npc_target_position_system",
+                &["move_player_target_position_system"],
+            )
             .with(
                 MoveCurrentPositionSystem,
                 "move_current_position_system",
-                &["move_player_target_position_system"],
+                &[
+                    "move_player_target_position_system",
+                    "move_npc_target_position_system",
+                ],
             )
             .with(
                 FollowPlayerSystem,
                 "follow_player_system",
                 &["move_current_position_system"],
             )
+            .with(
+                FireTriggersSystem::default(),
+                "fire_triggers_system",
+                &["move_current_position_system"],
+            )
+            .with(
+                PressPlatesSystem,
+                "press_plates_system",
+                &["move_current_position_system"],
+            )
+            .with(
+                OpenGatesSystem,
+                "open_gates_system",
+                &["press_plates_system"],
+            )
+            .with(
+                AdvanceQuestsSystem,
+                "advance_quests_system",
+                &["press_plates_system"],
+            )
             .with(
                 UpdateDrawParamSystem,
                 "update_draw_param_system",
@@ -110,7 +162,10 @@ impl OverworldScene {
             .with(
                 UpdateSpriteSheetDrawParamSystem,
                 "update_sprite_sheet_draw_param_system",
-                &["move_player_target_position_system"],
+                &[
+                    "move_player_target_position_system",
+                    "move_npc_target_position_system",
+                ],
             )
             .with(
                 FillTileMapToDrawSystem,
@@ -118,6 +173,7 @@ impl OverworldScene {
                 &[
                     "follow_player_system",
                     "update_sprite_sheet_draw_param_system",
+                    "open_gates_system",
                 ],
             )
             .with(
@@ -133,6 +189,7 @@ impl OverworldScene {
                 &[
                     "animate_background_system",
                     "move_player_target_position_system",
+                    "move_npc_target_position_system",
                     "follow_player_system",
                 ],
             )
@@ -148,6 +205,10 @@ impl OverworldScene {
             SpriteRow::new(2), // WALK RIGHT
             SpriteRow::new(2), // WALK UP
             SpriteRow::new(2), // WALK LEFT
+            SpriteRow::new(2), // RUN DOWN
+            SpriteRow::new(2), // RUN RIGHT
+            SpriteRow::new(2), // RUN UP
+            SpriteRow::new(2), // RUN LEFT
         ]);
 
         let player_width = player_image.width() as f32 / player_spritesheet.row().frames as f32;
@@ -187,7 +248,9 @@ impl OverworldScene {
             .with(Drawable {
                 drawable: Arc::new(player_image),
                 draw_params: player_draw_param,
+                offset_y: 0.,
             })
+            .with(ImageFile::new(PLAYER_FILE))
             .with(player_spritesheet)
             .with(player_facing_direction)
             .build();
@@ -205,12 +268,33 @@ impl std::fmt::Debug for OverworldScene {
     }
 }
 
+// Rows without any tiles around the camera don't have a batch to draw
+fn draw_row(
+    ctx: &mut ggez::Context,
+    layer: &TileMapLayer,
+    y: usize,
+    param: ggez::graphics::DrawParam,
+) -> GameResult {
+    if let Some(spritesheet) = layer.spritesheet(y) {
+        spritesheet.draw(ctx, param)?;
+    }
+
+    Ok(())
+}
+
 impl Scene for OverworldScene {
     fn dispose(&mut self, game_state: &mut GameState, _ctx: &mut ggez::Context) -> GameResult {
         game_state.world.remove::<Camera>();
         game_state.world.remove::<PlayerMovementRequest>();
         game_state.world.remove::<ShouldUpdateBackgroundTiles>();
         game_state.world.remove::<DoorRequest>();
+        game_state.world.remove::<ConnectionRequest>();
+        game_state.world.remove::<WarpRequest>();
+        game_state.world.remove::<CutsceneRequest>();
+        game_state.world.remove::<TriggerRequest>();
+        game_state.world.remove::<QuestUpdates>();
+        game_state.world.remove::<Rng>();
+        game_state.world.remove::<ScriptEngine>();
 
         if let Err(e) = game_state.world.delete_entities(self.entities.as_slice()) {
             return Err(ggez::GameError::CustomError(format!(
@@ -237,31 +321,187 @@ impl Scene for OverworldScene {
     fn update(
         &mut self,
         game_state: &mut GameState,
-        _ctx: &mut ggez::Context,
+        ctx: &mut ggez::Context,
         _delta_secs: f32,
     ) -> GameResult<Option<SceneSwitch>> {
         self.dispatcher.dispatch(&game_state.world);
 
-        let mut door_request = game_state.world.fetch_mut::<DoorRequest>();
-        if let Some(door) = door_request.requesting.take() {
-            let scene_builder: SceneBuilder = door.to_map.scene_builder_from_door(door.to_id)?;
+        let door = game_state
+            .world
+            .fetch_mut::<DoorRequest>()
+            .requesting
+            .take();
+        if let Some(door) = door {
+            let scene_builder: SceneBuilder = door.to_map.scene_builder_from_door(door.to_id);
+
+            return Ok(Some(SceneSwitch::Transition(
+                Transition::fade(),
+                Box::new(SceneSwitch::ReplaceTop(scene_builder)),
+            )));
+        }
+
+        let step = game_state
+            .world
+            .fetch_mut::<ConnectionRequest>()
+            .requesting
+            .take();
+        if let Some(step) = step {
+            let scene_builder: SceneBuilder = step
+                .to_map
+                .scene_builder_from_connection((step.x, step.y), step.direction);
 
             return Ok(Some(SceneSwitch::ReplaceTop(scene_builder)));
         }
 
+        let warp = game_state
+            .world
+            .fetch_mut::<WarpRequest>()
+            .requesting
+            .take();
+        if let Some(warp) = warp {
+            let scene_builder: SceneBuilder = warp
+                .to_map
+                .scene_builder_from_warp((warp.x, warp.y), warp.facing);
+
+            return Ok(Some(SceneSwitch::Transition(
+                Transition::circle_wipe(),
+                Box::new(SceneSwitch::ReplaceTop(scene_builder)),
+            )));
+        }
+
+        let functions =
+            std::mem::take(&mut game_state.world.fetch_mut::<TriggerRequest>().requesting);
+        if !functions.is_empty() {
+            let map = game_state.world.fetch::<SaveData>().player.map.clone();
+            let script = game_state
+                .world
+                .fetch::<MapRegistry>()
+                .get(&map)?
+                .script
+                .clone();
+
+            match script {
+                Some(script) => {
+                    let commands: Vec<_> = functions
+                        .iter()
+                        .flat_map(|function| {
+                            ScriptEngine::run(&game_state.world, ctx, &script, function)
+                        })
+                        .collect();
+
+                    // Whatever the triggers ask for plays out as one cutscene
+                    if !commands.is_empty() {
+                        game_state
+                            .world
+                            .fetch_mut::<CutsceneRequest>()
+                            .requesting
+                            .get_or_insert_with(Vec::new)
+                            .extend(commands);
+                    }
+                }
+                None => log::warn!(
+                    "Triggers in {} went off, but it doesn't have a script for {}",
+                    map,
+                    functions.join(", ")
+                ),
+            }
+        }
+
+        // TODO: Presentation, task updates are only printed for now
+        let updates = std::mem::take(&mut game_state.world.fetch_mut::<QuestUpdates>().updates);
+        for update in updates {
+            log::info!(
+                "{} in {} went from {:?} to {:?}",
+                update.task,
+                update.quest,
+                update.from,
+                update.to
+            );
+
+            let heading = match update.to {
+                TaskStatus::Active => "New task",
+                TaskStatus::Complete => "Task complete",
+                TaskStatus::Failed => "Task failed",
+                TaskStatus::Unknown | TaskStatus::NotStarted => continue,
+            };
+
+            // Quests can be reloaded out from under their updates
+            let quest_registry = game_state.world.fetch::<QuestRegistry>();
+            let (quest_info, task_info) = match quest_registry
+                .get(&update.quest)
+                .and_then(|quest_info| Ok((quest_info, quest_info.get(&update.task)?)))
+            {
+                Ok(found) => found,
+                Err(e) => {
+                    log::warn!("{}", e);
+                    continue;
+                }
+            };
+
+            println!(
+                "{} in {}: {}",
+                heading, quest_info.name, task_info.description
+            );
+        }
+
+        let mut cutscene_request = game_state.world.fetch_mut::<CutsceneRequest>();
+        if let Some(commands) = cutscene_request.requesting.take() {
+            let scene_builder: SceneBuilder = Box::new(move |game_state, _| {
+                let scene = CutsceneScene::new(game_state, commands.clone());
+                Ok(Rc::new(RefCell::new(scene)))
+            });
+
+            return Ok(Some(SceneSwitch::Push(scene_builder)));
+        }
+
         Ok(None)
     }
 
     #[tracing::instrument]
     fn draw(&self, game_state: &GameState, ctx: &mut ggez::Context) -> GameResult {
         if let Some(tile_map) = game_state.world.try_fetch::<TileMap>() {
-            tile_map.background.draw(ctx, tile_map.spritesheet_param)?;
+            // Connected maps only show past the edges, so they go underneath this one
+            for connected_map in &tile_map.connected_maps {
+                let connected_tile_map = &connected_map.tile_map;
+                let param = connected_tile_map.spritesheet_param;
+
+                for layer in connected_tile_map.layers_by_draw_order(DrawOrder::Below) {
+                    draw_row(ctx, layer, 0, param)?;
+                }
 
-            for drawable in &tile_map.to_draw {
-                drawable.drawable.draw(ctx, drawable.draw_params)?;
+                for layer in connected_tile_map.layers_by_draw_order(DrawOrder::Sorted) {
+                    for spritesheet in layer.spritesheets() {
+                        spritesheet.draw(ctx, param)?;
+                    }
+                }
+
+                for layer in connected_tile_map.layers_by_draw_order(DrawOrder::Above) {
+                    draw_row(ctx, layer, 0, param)?;
+                }
             }
 
-            tile_map.overlay.draw(ctx, tile_map.spritesheet_param)?;
+            for layer in tile_map.layers_by_draw_order(DrawOrder::Below) {
+                draw_row(ctx, layer, 0, tile_map.spritesheet_param)?;
+            }
+
+            // Sorted layers are drawn row by row, interleaved with entities on the same row
+            let camera = game_state.world.fetch::<Camera>();
+            let mut to_draw = tile_map.to_draw.iter().peekable();
+
+            for y in camera.top..camera.bottom {
+                for layer in tile_map.layers_by_draw_order(DrawOrder::Sorted) {
+                    draw_row(ctx, layer, y, tile_map.spritesheet_param)?;
+                }
+
+                while let Some((_, drawable)) = to_draw.peek().filter(|(row, _)| *row == y) {
+                    drawable.drawable.draw(ctx, drawable.draw_params)?;
+                    to_draw.next();
+                }
+            }
+
+            for layer in tile_map.layers_by_draw_order(DrawOrder::Above) {
+                draw_row(ctx, layer, 0, tile_map.spritesheet_param)?;
+            }
         }
 
         Ok(())
@@ -274,6 +514,7 @@ impl Scene for OverworldScene {
         input: GameInput,
     ) -> GameResult<Option<SceneSwitch>> {
         let mut direction_to_turn = None;
+        let mut interaction = None;
 
         if let Some(player_movement_request) = game_state.world.get_mut::<PlayerMovementRequest>() {
             match input {
@@ -288,6 +529,7 @@ impl Scene for OverworldScene {
                             player_movement_request.last_requested_direction = None;
                             player_movement_request.last_requested_x_direction = None;
                             player_movement_request.last_requested_y_direction = None;
+                            player_movement_request.movement_mode = MovementMode::Walk;
 
                             return Ok(Some(SceneSwitch::Push(scene_builder)));
                         }
@@ -311,8 +553,7 @@ impl Scene for OverworldScene {
                                     let target_position = target_position as &mut TargetPosition;
 
                                     if !target_position.is_moving {
-                                        let height = tile_map.tiles.len();
-                                        let width = tile_map.tiles[0].len();
+                                        let (width, height) = tile_map.grid.dimensions();
 
                                         if let Some((dx, dy)) = match facing_direction.direction {
                                             GameDirection::Down => {
@@ -349,11 +590,8 @@ impl Scene for OverworldScene {
                                             let y =
                                                 (target_position.y as isize + dy).max(0) as usize;
 
-                                            if let Some(target_entity) =
-                                                tile_map.get_tile(x, y).entity
+                                            if let Some(target_entity) = tile_map.grid.entity(x, y)
                                             {
-                                                // TODO: if entity has a spritesheet and/or a facing direction, then turn entity to face player
-
                                                 // TODO: get interactable component from entity which can define how they should interact
 
                                                 if let Some(interactable) = game_state
@@ -362,11 +600,11 @@ impl Scene for OverworldScene {
                                                     .get(target_entity)
                                                 {
                                                     let player_entity = tile_map
-                                                        .get_tile(
+                                                        .grid
+                                                        .entity(
                                                             target_position.x,
                                                             target_position.y,
                                                         )
-                                                        .entity
                                                         .expect(
                                                             "Player is not in target position?",
                                                         );
@@ -376,9 +614,11 @@ impl Scene for OverworldScene {
                                                     if let Some(scene_builder) =
                                                         handler(player_entity, target_entity)
                                                     {
-                                                        return Ok(Some(SceneSwitch::Push(
+                                                        interaction = Some((
+                                                            target_entity,
+                                                            facing_direction.direction,
                                                             scene_builder,
-                                                        )));
+                                                        ));
                                                     }
                                                 }
                                             }
@@ -388,6 +628,13 @@ impl Scene for OverworldScene {
                             }
                         }
                     }
+                    GameButton::Run => {
+                        player_movement_request.movement_mode = if pressed {
+                            MovementMode::Run
+                        } else {
+                            MovementMode::Walk
+                        };
+                    }
                     GameButton::Up => {
                         player_movement_request.last_requested_direction = if pressed {
                             Some(GameDirection::Up)
@@ -442,6 +689,25 @@ impl Scene for OverworldScene {
             }
         }
 
+        // Turned after the interaction is found, so nothing is still borrowing the world
+        if let Some((target_entity, player_direction, scene_builder)) = interaction {
+            FacingPlayer::face(&mut game_state.world, target_entity, player_direction)?;
+
+            let entity_name = game_state
+                .world
+                .read_component::<EntityName>()
+                .get(target_entity)
+                .cloned();
+            if let Some(entity_name) = entity_name {
+                game_state
+                    .world
+                    .fetch::<QuestRegistry>()
+                    .talked_to(&mut game_state.world.fetch_mut::<SaveData>(), &entity_name);
+            }
+
+            return Ok(Some(SceneSwitch::Push(scene_builder)));
+        }
+
         if let Some(direction) = direction_to_turn {
             let (player_c, target_position_c, mut timer_c, mut facing_direction_c): (
                 specs::ReadStorage<Player>,
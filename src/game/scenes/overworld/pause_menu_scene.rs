@@ -2,16 +2,39 @@ use super::{
     error::types::GameResult,
     game_state::GameState,
     input::types::{GameButton, GameInput},
-    types::{Scene, SceneSwitch},
+    types::{Scene, SceneBuilder, SceneSwitch},
+    BagScene, JournalScene,
 };
+use std::{cell::RefCell, rc::Rc};
 
-pub struct PauseMenuScene;
+#[derive(Debug, Clone, Copy)]
+enum PauseMenuOption {
+    Bag,
+    Journal,
+}
+
+const OPTIONS: [PauseMenuOption; 2] = [PauseMenuOption::Bag, PauseMenuOption::Journal];
+
+pub struct PauseMenuScene {
+    selected: usize,
+}
 
 impl PauseMenuScene {
     pub fn new() -> Self {
         println!("Paused");
 
-        PauseMenuScene
+        let scene = PauseMenuScene { selected: 0 };
+        scene.print();
+
+        scene
+    }
+
+    // TODO: Presentation, this only prints the menu for now
+    fn print(&self) {
+        for (idx, option) in OPTIONS.iter().enumerate() {
+            let cursor = if idx == self.selected { ">" } else { " " };
+            println!("{} {:?}", cursor, option);
+        }
     }
 }
 
@@ -52,6 +75,28 @@ impl Scene for PauseMenuScene {
                         GameButton::Start | GameButton::Secondary => {
                             return Ok(Some(SceneSwitch::Pop))
                         }
+                        GameButton::Up => {
+                            self.selected = (self.selected + OPTIONS.len() - 1) % OPTIONS.len();
+                            self.print();
+                        }
+                        GameButton::Down => {
+                            self.selected = (self.selected + 1) % OPTIONS.len();
+                            self.print();
+                        }
+                        GameButton::Primary => {
+                            let scene_builder: SceneBuilder = match OPTIONS[self.selected] {
+                                PauseMenuOption::Bag => Box::new(|game_state, _| {
+                                    let scene = BagScene::new(game_state);
+                                    Ok(Rc::new(RefCell::new(scene)))
+                                }),
+                                PauseMenuOption::Journal => Box::new(|game_state, _| {
+                                    let scene = JournalScene::new(game_state);
+                                    Ok(Rc::new(RefCell::new(scene)))
+                                }),
+                            };
+
+                            return Ok(Some(SceneSwitch::ReplaceTop(scene_builder)));
+                        }
                         _ => {}
                     }
                 }
@@ -1,45 +1,23 @@
 use super::{
-    ecs::components::Interactable,
+    ecs::components::FacingPlayer,
     error::types::GameResult,
     game_state::GameState,
     input::types::{GameButton, GameInput},
-    types::SceneBuilder,
     types::{Scene, SceneSwitch},
 };
-use specs::{Entity, WorldExt};
-use std::{cell::RefCell, rc::Rc};
+use specs::Entity;
+use std::{cell::Cell, rc::Rc};
 
-pub struct TextBoxScene;
+pub struct TextBoxScene {
+    target_entity: Option<Entity>,
+    // Lets whatever opened the text box know it's been read
+    closed: Option<Rc<Cell<bool>>>,
+}
 
 impl TextBoxScene {
-    pub fn new(
-        game_state: &mut GameState,
-        player_entity: Entity,
-        target_entity: Entity,
-        text: &str,
-    ) -> Self {
+    pub fn new(target_entity: Entity, text: &str) -> Self {
         println!("Text Box: {}", text);
 
-        if let Some(interactable) = game_state
-            .world
-            .write_component::<Interactable>()
-            .get_mut(target_entity)
-        {
-            interactable.handler = Box::new(|player_entity, target_entity| {
-                let scene_builder: SceneBuilder = Box::new(move |game_state, _| {
-                    let scene = Self::new(
-                        game_state,
-                        player_entity,
-                        target_entity,
-                        &format!("{:?} says LOL to: {:?}", target_entity, player_entity),
-                    );
-                    Ok(Rc::new(RefCell::new(scene)))
-                });
-
-                Some(scene_builder)
-            });
-        }
-
         // TODO: Presentation:
         // - Show text box with text
         // - Allow for "multi-page" text boxes depending on length of text
@@ -47,7 +25,20 @@ impl TextBoxScene {
         // - Allow user input to answer questions?
         // - Handle user's locale using "Fluent"
 
-        TextBoxScene
+        TextBoxScene {
+            target_entity: Some(target_entity),
+            closed: None,
+        }
+    }
+
+    // Text that isn't coming from anything in the map, e.g. in a cutscene
+    pub fn from_text(text: &str, closed: Rc<Cell<bool>>) -> Self {
+        println!("Text Box: {}", text);
+
+        TextBoxScene {
+            target_entity: None,
+            closed: Some(closed),
+        }
     }
 }
 
@@ -58,7 +49,15 @@ impl std::fmt::Debug for TextBoxScene {
 }
 
 impl Scene for TextBoxScene {
-    fn dispose(&mut self, _game_state: &mut GameState, _ctx: &mut ggez::Context) -> GameResult {
+    fn dispose(&mut self, game_state: &mut GameState, _ctx: &mut ggez::Context) -> GameResult {
+        if let Some(target_entity) = self.target_entity {
+            FacingPlayer::stop(&mut game_state.world, target_entity);
+        }
+
+        if let Some(closed) = &self.closed {
+            closed.set(true);
+        }
+
         Ok(())
     }
 
@@ -0,0 +1,199 @@
+use super::{error::types::GameResult, input::types::GameDirection, types::SceneSwitch};
+use ggez::graphics::{Color, DrawMode, DrawParam, Mesh, Rect};
+
+const TRANSITION_SECONDS: f32 = 0.3;
+
+// How the screen is covered up or uncovered
+#[derive(Debug, Clone, Copy)]
+pub enum Curtain {
+    Fade,
+    CircleWipe,
+    // Comes in from the edge behind the direction, and leaves out the edge in front of it
+    Slide(GameDirection),
+}
+
+// The screen is covered, the scenes are switched behind it, then the screen is uncovered.
+// Without a curtain that half happens straight away
+#[derive(Debug, Clone, Copy)]
+pub struct Transition {
+    pub cover: Option<Curtain>,
+    pub uncover: Option<Curtain>,
+    // For each half
+    pub seconds: f32,
+}
+
+impl Transition {
+    pub fn fade() -> Self {
+        Self::both(Curtain::Fade)
+    }
+
+    pub fn fade_to_black() -> Self {
+        Self {
+            cover: Some(Curtain::Fade),
+            uncover: None,
+            seconds: TRANSITION_SECONDS,
+        }
+    }
+
+    pub fn fade_from_black() -> Self {
+        Self {
+            cover: None,
+            uncover: Some(Curtain::Fade),
+            seconds: TRANSITION_SECONDS,
+        }
+    }
+
+    pub fn circle_wipe() -> Self {
+        Self::both(Curtain::CircleWipe)
+    }
+
+    pub fn slide(direction: GameDirection) -> Self {
+        Self::both(Curtain::Slide(direction))
+    }
+
+    fn both(curtain: Curtain) -> Self {
+        Self {
+            cover: Some(curtain),
+            uncover: Some(curtain),
+            seconds: TRANSITION_SECONDS,
+        }
+    }
+
+    fn cover_seconds(&self) -> f32 {
+        self.cover.map_or(0., |_| self.seconds)
+    }
+
+    fn uncover_seconds(&self) -> f32 {
+        self.uncover.map_or(0., |_| self.seconds)
+    }
+}
+
+pub struct RunningTransition {
+    transition: Transition,
+    elapsed: f32,
+    // Taken once the screen is covered
+    switch: Option<SceneSwitch>,
+}
+
+impl RunningTransition {
+    pub fn new(transition: Transition, switch: SceneSwitch) -> Self {
+        Self {
+            transition,
+            elapsed: 0.,
+            switch: Some(switch),
+        }
+    }
+
+    pub fn is_switching(&self) -> bool {
+        self.switch.is_some()
+    }
+
+    pub fn finished(&self) -> bool {
+        self.switch.is_none()
+            && self.elapsed >= self.transition.cover_seconds() + self.transition.uncover_seconds()
+    }
+
+    // Gives back the switch once the screen is covered
+    pub fn tick(&mut self, delta_secs: f32) -> Option<SceneSwitch> {
+        // Loading the next scene can take a while, which shouldn't skip the uncovering
+        if self.switch.is_some() {
+            self.elapsed = (self.elapsed + delta_secs).min(self.transition.cover_seconds());
+        } else {
+            self.elapsed += delta_secs;
+        }
+
+        if self.elapsed >= self.transition.cover_seconds() {
+            return self.switch.take();
+        }
+
+        None
+    }
+
+    pub fn draw(&self, ctx: &mut ggez::Context) -> GameResult {
+        let cover_seconds = self.transition.cover_seconds();
+
+        if self.switch.is_some() {
+            // Curtains without a cover start out covered
+            return match self.transition.cover {
+                Some(curtain) => draw_curtain(ctx, curtain, self.elapsed / cover_seconds, true),
+                None => draw_curtain(ctx, Curtain::Fade, 1., true),
+            };
+        }
+
+        match self.transition.uncover {
+            Some(curtain) => {
+                let percent = (self.elapsed - cover_seconds) / self.transition.uncover_seconds();
+                draw_curtain(ctx, curtain, 1. - percent.min(1.), false)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+// Covered is how much of the screen the curtain is over, from 0 to 1
+fn draw_curtain(
+    ctx: &mut ggez::Context,
+    curtain: Curtain,
+    covered: f32,
+    is_covering: bool,
+) -> GameResult {
+    let covered = covered.max(0.).min(1.);
+
+    if covered <= 0. {
+        return Ok(());
+    }
+
+    let screen = ggez::graphics::screen_coordinates(ctx);
+
+    let mesh = match curtain {
+        Curtain::Fade => Mesh::new_rectangle(
+            ctx,
+            DrawMode::fill(),
+            screen,
+            Color::new(0., 0., 0., covered),
+        )?,
+        Curtain::CircleWipe => {
+            // A ring around the hole in the middle, wide enough to reach the corners
+            let outer_radius = (screen.w * screen.w + screen.h * screen.h).sqrt() / 2.;
+            let inner_radius = outer_radius * (1. - covered);
+            let width = outer_radius - inner_radius;
+
+            Mesh::new_circle(
+                ctx,
+                DrawMode::stroke(width),
+                [screen.x + screen.w / 2., screen.y + screen.h / 2.],
+                inner_radius + width / 2.,
+                0.5,
+                ggez::graphics::BLACK,
+            )?
+        }
+        Curtain::Slide(direction) => {
+            let (w, h) = match direction {
+                GameDirection::Left | GameDirection::Right => (screen.w * covered, screen.h),
+                GameDirection::Up | GameDirection::Down => (screen.w, screen.h * covered),
+            };
+
+            // Covering grows from the edge the curtain comes in at, uncovering shrinks towards
+            // the edge it leaves out of
+            let from_far_edge = match direction {
+                GameDirection::Left | GameDirection::Up => is_covering,
+                GameDirection::Right | GameDirection::Down => !is_covering,
+            };
+
+            let (x, y) = if from_far_edge {
+                (screen.x + screen.w - w, screen.y + screen.h - h)
+            } else {
+                (screen.x, screen.y)
+            };
+
+            Mesh::new_rectangle(
+                ctx,
+                DrawMode::fill(),
+                Rect::new(x, y, w, h),
+                ggez::graphics::BLACK,
+            )?
+        }
+    };
+
+    ggez::graphics::draw(ctx, &mesh, DrawParam::default())
+}
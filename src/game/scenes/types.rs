@@ -1,4 +1,9 @@
-use super::{error::types::GameResult, game_state::GameState, input::types::GameInput};
+use super::{
+    error::types::GameResult,
+    game_state::GameState,
+    input::types::GameInput,
+    transition::{RunningTransition, Transition},
+};
 use std::{cell::RefCell, rc::Rc};
 
 pub type SceneBuilder =
@@ -9,6 +14,8 @@ pub enum SceneSwitch {
     Push(SceneBuilder),
     ReplaceTop(SceneBuilder),
     ReplaceAll(SceneBuilder),
+    // Covers the screen before switching, and input is ignored until it's uncovered again
+    Transition(Transition, Box<SceneSwitch>),
 }
 
 pub trait Scene {
@@ -101,6 +108,7 @@ pub struct SceneManager {
     input_stack: Vec<Rc<RefCell<dyn Scene>>>,
     update_stack: Vec<Rc<RefCell<dyn Scene>>>,
     draw_stack: Vec<Rc<RefCell<dyn Scene>>>,
+    transition: Option<RunningTransition>,
 }
 
 impl std::fmt::Debug for SceneManager {
@@ -149,6 +157,51 @@ impl SceneManager {
         &self.draw_stack
     }
 
+    pub fn is_transitioning(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    // Scenes are paused while the screen is being covered, the old scene is only drawn until the
+    // switch happens
+    pub fn is_switching(&self) -> bool {
+        self.transition
+            .as_ref()
+            .map_or(false, |transition| transition.is_switching())
+    }
+
+    pub fn update_transition(
+        &mut self,
+        game_state: &mut GameState,
+        ctx: &mut ggez::Context,
+        delta_secs: f32,
+    ) -> GameResult<Option<Rc<RefCell<dyn Scene>>>> {
+        let switch = match &mut self.transition {
+            Some(transition) => transition.tick(delta_secs),
+            None => return Ok(None),
+        };
+
+        let popped = match switch {
+            Some(switch) => self.switch(game_state, ctx, switch)?,
+            None => None,
+        };
+
+        if let Some(transition) = &self.transition {
+            if transition.finished() {
+                self.transition = None;
+            }
+        }
+
+        Ok(popped)
+    }
+
+    pub fn draw_transition(&self, ctx: &mut ggez::Context) -> GameResult {
+        if let Some(transition) = &self.transition {
+            transition.draw(ctx)?;
+        }
+
+        Ok(())
+    }
+
     pub fn push(&mut self, ctx: &mut ggez::Context, scene: Rc<RefCell<dyn Scene>>) {
         ggez::graphics::clear(ctx, ggez::graphics::BLACK);
 
@@ -409,6 +462,9 @@ impl SceneManager {
             }
             SceneSwitch::ReplaceTop(builder) => self.replace_top(game_state, ctx, builder)?,
             SceneSwitch::ReplaceAll(builder) => self.replace_all(game_state, ctx, builder)?,
+            SceneSwitch::Transition(transition, switch) => {
+                return self.transition(game_state, ctx, transition, *switch)
+            }
         };
 
         Ok(None)
@@ -441,8 +497,29 @@ impl SceneManager {
             SceneSwitch::ReplaceAll(builder) => {
                 self.unchecked_replace_all(game_state, ctx, builder)?
             }
+            SceneSwitch::Transition(transition, switch) => {
+                return self.transition(game_state, ctx, transition, *switch)
+            }
         };
 
         Ok(None)
     }
+
+    // The switch waits until the screen is covered. One already running keeps its curtain, so the
+    // new switch happens straight away
+    fn transition(
+        &mut self,
+        game_state: &mut GameState,
+        ctx: &mut ggez::Context,
+        transition: Transition,
+        switch: SceneSwitch,
+    ) -> GameResult<Option<Rc<RefCell<dyn Scene>>>> {
+        if self.transition.is_some() {
+            return self.switch(game_state, ctx, switch);
+        }
+
+        self.transition = Some(RunningTransition::new(transition, switch));
+
+        Ok(None)
+    }
 }
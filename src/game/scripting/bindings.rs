@@ -0,0 +1,230 @@
+// Everything scripts can call. Names of states, quests, entities and so on are passed as strings.
+// Quests, tasks and items are checked against their registries once the command runs
+use super::{
+    super::{
+        ecs::{
+            components::{EntityName, ItemId, MapName, QuestName, StateName, TaskName},
+            resources::{CutsceneCommand, CutsceneTarget},
+        },
+        input::types::GameDirection,
+        save::{QuestDefinition, TaskStatus},
+    },
+    lock, ScriptRun,
+};
+use rhai::{EvalAltResult, FLOAT, INT};
+use serde::de::{value::StrDeserializer, DeserializeOwned, IntoDeserializer};
+use std::sync::{Arc, Mutex};
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn parse<T: DeserializeOwned>(kind: &str, name: &str) -> ScriptResult<T> {
+    let deserializer: StrDeserializer<serde::de::value::Error> = name.into_deserializer();
+
+    T::deserialize(deserializer).map_err(|_| format!("{:?} isn't a {}", name, kind).into())
+}
+
+fn parse_target(name: &str) -> ScriptResult<CutsceneTarget> {
+    match name {
+        "Player" => Ok(CutsceneTarget::Player),
+        _ => Ok(CutsceneTarget::Entity(EntityName::new(name))),
+    }
+}
+
+fn parse_usize(kind: &str, tiles: INT) -> ScriptResult<usize> {
+    if tiles < 0 {
+        return Err(format!("{} can't be negative, found {}", kind, tiles).into());
+    }
+
+    Ok(tiles as usize)
+}
+
+pub fn register(engine: &mut rhai::Engine, run: &Arc<Mutex<ScriptRun>>) {
+    // Dialog
+
+    let say_run = Arc::clone(run);
+    engine.register_fn("say", move |text: &str| {
+        lock(&say_run).commands.push(CutsceneCommand::ShowText {
+            text: text.to_string(),
+        });
+    });
+
+    // Flags
+
+    let has_flag_run = Arc::clone(run);
+    engine.register_fn("has_flag", move |state: &str| -> bool {
+        lock(&has_flag_run).states.contains(&StateName::new(state))
+    });
+
+    let set_flag_run = Arc::clone(run);
+    engine.register_fn("set_flag", move |state: &str| {
+        let state = StateName::new(state);
+
+        let mut run = lock(&set_flag_run);
+        run.states.insert(state.clone());
+        run.commands
+            .push(CutsceneCommand::SetState { state, on: true });
+    });
+
+    let clear_flag_run = Arc::clone(run);
+    engine.register_fn("clear_flag", move |state: &str| {
+        let state = StateName::new(state);
+
+        let mut run = lock(&clear_flag_run);
+        run.states.remove(&state);
+        run.commands
+            .push(CutsceneCommand::SetState { state, on: false });
+    });
+
+    // Quest tasks
+
+    let task_status_run = Arc::clone(run);
+    engine.register_fn("task_status", move |quest: &str, task: &str| -> String {
+        let quest = QuestName::new(quest);
+        let task = TaskName::new(task);
+
+        let status = lock(&task_status_run)
+            .journal
+            .get(&quest)
+            .and_then(|quest_definition| quest_definition.tasks.get(&task).cloned())
+            .unwrap_or(TaskStatus::Unknown);
+
+        format!("{:?}", status)
+    });
+
+    let set_task_run = Arc::clone(run);
+    engine.register_fn(
+        "set_task",
+        move |quest: &str, task: &str, status: &str| -> ScriptResult<()> {
+            let quest = QuestName::new(quest);
+            let task = TaskName::new(task);
+            let status: TaskStatus = parse("task status", status)?;
+
+            let mut run = lock(&set_task_run);
+            run.journal
+                .entry(quest.clone())
+                .or_insert_with(QuestDefinition::new)
+                .tasks
+                .insert(task.clone(), status.clone());
+            run.commands.push(CutsceneCommand::SetTask {
+                quest,
+                task,
+                status,
+            });
+
+            Ok(())
+        },
+    );
+
+    // Items
+
+    let item_count_run = Arc::clone(run);
+    engine.register_fn("item_count", move |item: &str| -> INT {
+        lock(&item_count_run).inventory.quantity(&ItemId::new(item)) as INT
+    });
+
+    // The bag's limits are only checked once the command runs, so the script sees everything given
+    let give_item_run = Arc::clone(run);
+    engine.register_fn(
+        "give_item",
+        move |item: &str, quantity: INT| -> ScriptResult<()> {
+            let item = ItemId::new(item);
+            let quantity = parse_usize("quantity", quantity)?;
+
+            let mut run = lock(&give_item_run);
+            run.inventory.add(&item, quantity, usize::MAX);
+            run.commands
+                .push(CutsceneCommand::GiveItem { item, quantity });
+
+            Ok(())
+        },
+    );
+
+    // Returns whether there was enough to take, nothing is taken when there isn't
+    let take_item_run = Arc::clone(run);
+    engine.register_fn(
+        "take_item",
+        move |item: &str, quantity: INT| -> ScriptResult<bool> {
+            let item = ItemId::new(item);
+            let quantity = parse_usize("quantity", quantity)?;
+
+            let mut run = lock(&take_item_run);
+            if !run.inventory.remove(&item, quantity) {
+                return Ok(false);
+            }
+            run.commands
+                .push(CutsceneCommand::TakeItem { item, quantity });
+
+            Ok(true)
+        },
+    );
+
+    // Movement
+
+    let walk_run = Arc::clone(run);
+    engine.register_fn(
+        "walk",
+        move |target: &str, direction: &str, tiles: INT| -> ScriptResult<()> {
+            let command = CutsceneCommand::Move {
+                target: parse_target(target)?,
+                direction: parse("direction", direction)?,
+                tiles: parse_usize("tiles", tiles)?,
+            };
+
+            lock(&walk_run).commands.push(command);
+
+            Ok(())
+        },
+    );
+
+    let face_run = Arc::clone(run);
+    engine.register_fn(
+        "face",
+        move |target: &str, direction: &str| -> ScriptResult<()> {
+            let command = CutsceneCommand::Face {
+                target: parse_target(target)?,
+                direction: parse("direction", direction)?,
+            };
+
+            lock(&face_run).commands.push(command);
+
+            Ok(())
+        },
+    );
+
+    let wait_run = Arc::clone(run);
+    engine.register_fn("wait", move |seconds: FLOAT| {
+        lock(&wait_run).commands.push(CutsceneCommand::Wait {
+            seconds: seconds as f32,
+        });
+    });
+
+    let pan_camera_run = Arc::clone(run);
+    engine.register_fn("pan_camera", move |x: FLOAT, y: FLOAT, seconds: FLOAT| {
+        lock(&pan_camera_run)
+            .commands
+            .push(CutsceneCommand::PanCamera {
+                x: x as f32,
+                y: y as f32,
+                seconds: seconds as f32,
+            });
+    });
+
+    let warp_run = Arc::clone(run);
+    engine.register_fn(
+        "warp",
+        move |map: &str, x: INT, y: INT, facing: &str| -> ScriptResult<()> {
+            let facing: GameDirection = parse("direction", facing)?;
+
+            let command = CutsceneCommand::Warp {
+                map: MapName::new(map),
+                x: parse_usize("x", x)?,
+                y: parse_usize("y", y)?,
+                facing,
+            };
+
+            lock(&warp_run).commands.push(command);
+
+            Ok(())
+        },
+    );
+}
@@ -0,0 +1,162 @@
+// Scripts are read from assets/ when they're first run, so dialog and events can change without
+// rebuilding the game. Running a script doesn't touch the world, it queues up cutscene commands
+mod bindings;
+
+use super::{
+    ecs::{
+        components::{QuestName, StateName},
+        resources::CutsceneCommand,
+    },
+    error::types::GameResult,
+    save::{Inventory, QuestDefinition, SaveData},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Read,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+// Stops a script stuck in a loop from freezing the game
+const MAX_OPERATIONS: u64 = 100_000;
+
+// What a script can see and what it's asked for while it runs. Flags, tasks and items it changes
+// are seen by the rest of the script straight away, but only saved once the commands get to them
+#[derive(Default)]
+struct ScriptRun {
+    states: HashSet<StateName>,
+    journal: HashMap<QuestName, QuestDefinition>,
+    inventory: Inventory,
+    commands: Vec<CutsceneCommand>,
+}
+
+// The run is reset before every call, so whatever a panicking binding left behind doesn't matter
+fn lock(run: &Mutex<ScriptRun>) -> MutexGuard<'_, ScriptRun> {
+    run.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub struct ScriptEngine {
+    engine: rhai::Engine,
+    run: Arc<Mutex<ScriptRun>>,
+    scripts: HashMap<String, rhai::AST>,
+}
+
+impl std::fmt::Debug for ScriptEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!(
+            "ScriptEngine {{ scripts: {:?}, ... }}",
+            self.scripts.keys().collect::<Vec<_>>()
+        ))
+    }
+}
+
+impl ScriptEngine {
+    pub fn new() -> Self {
+        let run = Arc::new(Mutex::new(ScriptRun::default()));
+
+        let mut engine = rhai::Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.on_print(|text| log::info!("Script: {}", text));
+        engine.on_debug(|text, source, position| {
+            log::debug!("Script {} @ {}: {}", source.unwrap_or(""), position, text)
+        });
+
+        bindings::register(&mut engine, &run);
+
+        Self {
+            engine,
+            run,
+            scripts: HashMap::new(),
+        }
+    }
+
+    // Scripts are read again the next time they're run
+    pub fn clear(&mut self) {
+        self.scripts.clear();
+    }
+
+    // Broken scripts are logged and do nothing, so they can be fixed while the game is running
+    pub fn run(
+        world: &specs::World,
+        ctx: &mut ggez::Context,
+        filename: &str,
+        function: &str,
+    ) -> Vec<CutsceneCommand> {
+        let mut script_engine = world.fetch_mut::<ScriptEngine>();
+        let save_data = world.fetch::<SaveData>();
+
+        match script_engine.call(ctx, &save_data, filename, function) {
+            Ok(commands) => commands,
+            Err(e) => {
+                log::error!("{}", e);
+                vec![]
+            }
+        }
+    }
+
+    // Functions that aren't in the script do nothing, so maps only need the events they use
+    fn call(
+        &mut self,
+        ctx: &mut ggez::Context,
+        save_data: &SaveData,
+        filename: &str,
+        function: &str,
+    ) -> GameResult<Vec<CutsceneCommand>> {
+        if !self.scripts.contains_key(filename) {
+            let ast = self.compile(ctx, filename)?;
+            self.scripts.insert(filename.to_string(), ast);
+        }
+
+        let ast = &self.scripts[filename];
+
+        if !ast
+            .iter_functions()
+            .any(|f| f.name == function && f.params.is_empty())
+        {
+            return Ok(vec![]);
+        }
+
+        {
+            let mut run = lock(&self.run);
+            run.states = save_data.world.states.clone();
+            run.journal = save_data.player.journal.clone();
+            run.inventory = save_data.player.inventory.clone();
+            run.commands.clear();
+        }
+
+        let result =
+            self.engine
+                .call_fn::<rhai::Dynamic>(&mut rhai::Scope::new(), ast, function, ());
+
+        let commands = std::mem::take(&mut lock(&self.run).commands);
+
+        // Whatever the function returns is ignored, it's the commands it queued that matter
+        if let Err(e) = result {
+            return Err(ggez::GameError::CustomError(format!(
+                "Script {} failed in {}(): {}",
+                filename, function, e
+            )));
+        }
+
+        Ok(commands)
+    }
+
+    fn compile(&self, ctx: &mut ggez::Context, filename: &str) -> GameResult<rhai::AST> {
+        let mut file = ctx
+            .filesystem
+            .find_vfs(&ctx.filesystem.assets_path)
+            .ok_or_else(|| {
+                ggez::GameError::FilesystemError("Couldn't find asset filesystem:".to_string())
+            })?
+            .open(&std::path::PathBuf::from(filename))?;
+
+        let mut source = String::new();
+        file.read_to_string(&mut source)?;
+
+        let mut ast = self.engine.compile(&source).map_err(|e| {
+            ggez::GameError::CustomError(format!("Couldn't compile script {}: {}", filename, e))
+        })?;
+        ast.set_source(filename);
+
+        Ok(ast)
+    }
+}
@@ -16,7 +16,8 @@ pub fn load_settings(fs: &mut ggez::filesystem::Filesystem) -> GameResult<Option
     }
 
     let mut file = config_vfs.open(settings_path)?;
-    let c = Settings::from_toml_file(&mut file)?;
+    let mut c = Settings::from_toml_file(&mut file)?;
+    c.add_missing_default_mappings();
     Ok(Some(c))
 }
 
@@ -2,7 +2,7 @@ use super::{config, input::types::GameButton, GameResult};
 use ggez::{conf::FullscreenType, input::keyboard::KeyCode};
 use serde_derive::{Deserialize, Serialize};
 use serde_with::serde_as;
-use std::{collections::HashMap, io};
+use std::{collections::HashMap, hash::Hash, io};
 use toml;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -86,6 +86,25 @@ impl Default for VideoSettings {
     }
 }
 
+// Settings saved before a button existed have nothing mapped to it, so it gets its default inputs
+// as long as they aren't already used for something else
+fn add_missing_default_mappings<I: Eq + Hash>(
+    mappings: &mut HashMap<I, GameButton>,
+    default_mappings: HashMap<I, GameButton>,
+) {
+    let unmapped: Vec<GameButton> = default_mappings
+        .values()
+        .filter(|button| !mappings.values().any(|mapped| mapped == *button))
+        .copied()
+        .collect();
+
+    for (input, button) in default_mappings {
+        if unmapped.contains(&button) {
+            mappings.entry(input).or_insert(button);
+        }
+    }
+}
+
 fn build_default_controller_button_mappings() -> HashMap<gilrs::Button, GameButton> {
     let mut mappings = HashMap::new();
     mappings.insert(gilrs::Button::DPadUp, GameButton::Up);
@@ -99,6 +118,7 @@ fn build_default_controller_button_mappings() -> HashMap<gilrs::Button, GameButt
     mappings.insert(gilrs::Button::East, GameButton::Secondary);
     mappings.insert(gilrs::Button::South, GameButton::Primary);
     mappings.insert(gilrs::Button::West, GameButton::Primary);
+    mappings.insert(gilrs::Button::RightTrigger, GameButton::Run);
     mappings
 }
 
@@ -154,6 +174,7 @@ fn build_default_keyboard_mappings() -> HashMap<KeyCode, GameButton> {
     mappings.insert(KeyCode::Return, GameButton::Primary);
     mappings.insert(KeyCode::LShift, GameButton::Secondary);
     mappings.insert(KeyCode::RShift, GameButton::Secondary);
+    mappings.insert(KeyCode::Space, GameButton::Run);
     mappings.insert(KeyCode::Escape, GameButton::Start);
     mappings.insert(KeyCode::Delete, GameButton::Select);
     mappings.insert(KeyCode::Back, GameButton::Select);
@@ -241,6 +262,20 @@ impl Settings {
         Ok(())
     }
 
+    pub fn add_missing_default_mappings(&mut self) {
+        add_missing_default_mappings(
+            &mut self
+                .game_settings
+                .controller_settings
+                .controller_button_mappings,
+            build_default_controller_button_mappings(),
+        );
+        add_missing_default_mappings(
+            &mut self.game_settings.keyboard_settings.keyboard_mappings,
+            build_default_keyboard_mappings(),
+        );
+    }
+
     pub fn from_toml_file<R: io::Read>(file: &mut R) -> GameResult<Self> {
         let mut encoded = String::new();
         file.read_to_string(&mut encoded)?;